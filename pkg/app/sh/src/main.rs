@@ -4,8 +4,17 @@
 use lib::*;
 use crate::vec::Vec;
 use crate::string::String;
+use core::sync::atomic::{AtomicBool, Ordering};
+use signal::SigHandler;
 extern crate lib;
 
+/// Set by `Signal::Chld` as a child exits, to report background apps
+static CHILD_EXITED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_child_exit(_sig: Signal) {
+    CHILD_EXITED.store(true, Ordering::Relaxed);
+}
+
 fn main() -> isize {
     println!("starting shell...");
     shell();
@@ -17,7 +26,9 @@ fn shell(){
     // print!("\x1B[2J\x1B[1;1H");
     //当前路径
     let mut path = String::from("/");
+    signal::signal(Signal::Chld, SigHandler::Handler(on_child_exit));
     loop {
+        reap_jobs();
         //提示词
         print!("\x1b[32;1mykx@YSOS\x1b[0m:\x1b[34;1m{}\x1b[0m$ ",path);
         let op=stdin().read_line();
@@ -48,7 +59,7 @@ fn shell(){
             "cat" => {
                 if let Some(target_path) = ops.get(1) {
                     // 如果提供了路径参数，则列出该路径下的内容
-                    sys_cat(&normalize_path(&path, target_path));
+                    cat(&normalize_path(&path, target_path));
                 } else {
                     println!("Error: missing <file name>");
                     help(core::prelude::v1::Some("cat"));
@@ -63,11 +74,57 @@ fn shell(){
                     help(core::prelude::v1::Some("cd"));
                 }
             },
+            "mount" => {
+                if let (Some(source), Some(target)) = (ops.get(1), ops.get(2)) {
+                    if !sys_mount(source, &normalize_path(&path, target)) {
+                        println!("mount: failed to mount {}", source);
+                    }
+                } else {
+                    println!("Error: missing <source> or <target>");
+                    help(Some("mount"));
+                }
+            },
+            "umount" => {
+                if let Some(target) = ops.get(1) {
+                    if !sys_umount(&normalize_path(&path, target)) {
+                        println!("umount: failed to unmount {}", target);
+                    }
+                } else {
+                    println!("Error: missing <target>");
+                    help(Some("umount"));
+                }
+            },
+            "mkfifo" => {
+                if let Some(target) = ops.get(1) {
+                    if !fs::mkfifo(&normalize_path(&path, target)) {
+                        println!("mkfifo: cannot create {}", target);
+                    }
+                } else {
+                    println!("Error: missing <path>");
+                    help(Some("mkfifo"));
+                }
+            },
             "run" => {
-                if ops.len() > 1 {
-                    run(ops[1]);
+                let (args, background) = match ops[1..].split_last() {
+                    Some((&"&", args)) => (args, true),
+                    _ => (&ops[1..], false),
+                };
+                match parse_pipeline(args) {
+                    Some((apps, Some((file, append)))) => {
+                        run(&apps, Some((&normalize_path(&path, file), append)), background)
+                    }
+                    Some((apps, None)) => run(&apps, None, background),
+                    None => help(Some("run")),
                 }
             },
+            "kill" => match parse_kill(&ops[1..]) {
+                Some((pid, sig)) => {
+                    if !sys_kill(pid, sig) {
+                        println!("kill: cannot signal process #{}", pid);
+                    }
+                }
+                None => help(Some("kill")),
+            },
             "sleep"=>sleep(ops[1].parse().unwrap_or(0)),
             "help"=>{
                 let maybe_command = ops.get(1); // 尝试获取用户可能提供的命令参数
@@ -85,13 +142,17 @@ fn help(maybe_command: Option<&str>) {
         ("ps", "List all currently running processes."),
         ("ls", "List directory contents. Usage: ls [path]"),
         ("ls_app","List all available user programs."),
-        ("run", "Run a specified user program. Replace <app> with the name of the program. Usage: run <app>"),
+        ("run", "Run a specified user program. Replace <app> with the name of the program. Usage: run <app> [| <app>]... [> file | >> file] [&]"),
+        ("kill", "Send a signal to a process, TERM (15) by default. Usage: kill [-<signal number>] <pid>"),
         ("sleep", "Sleep for a specified number of milliseconds. Usage: sleep <ms>"),
         ("help", "Display this help message. Usage: help [command]"),
         ("clear", "Clear the screen."),
         ("exit", "Exit the shell."),
         ("cd", "Change the current directory. Usage: cd <path>"),
         ("cat", "Concatenate and print files to the standard output. Usage: cat <file>"),
+        ("mount", "Mount tmpfs or a partition like hda1 at a directory. Usage: mount <source> <dir>"),
+        ("umount", "Unmount the filesystem at a directory. Usage: umount <dir>"),
+        ("mkfifo", "Create a named pipe, e.g. in a tmpfs, to connect apps by path. Usage: mkfifo <path>"),
     ];
 
     match maybe_command {
@@ -114,9 +175,161 @@ fn help(maybe_command: Option<&str>) {
     }
 }
 
-fn run(name: &str){
-    let ret = sys_wait_pid(sys_spawn(format!("/app/{}",name).as_str()));
-    println!("{} exit with code {}", name, ret);
+fn cat(path: &str) {
+    let mut file = match fs::File::open(path) {
+        Some(file) => file,
+        None => {
+            println!("cat: {}: cannot open file", path);
+            return;
+        }
+    };
+
+    let mut buf = [0u8; 512];
+    while let Some(len) = file.read(&mut buf) {
+        if len == 0 {
+            break;
+        }
+        // a character may be split between two reads
+        stdout().write_bytes(&buf[..len]);
+    }
+}
+
+/// Split `app1 | app2 > file` into the apps and the redirection,
+/// the flag of which tells whether to append to the file
+fn parse_pipeline<'a>(args: &[&'a str]) -> Option<(Vec<&'a str>, Option<(&'a str, bool)>)> {
+    let (cmd, redirect) = match args {
+        [cmd @ .., op, file] if *op == ">" || *op == ">>" => (cmd, Some((*file, *op == ">>"))),
+        _ => (args, None),
+    };
+
+    let apps = cmd
+        .split(|arg| *arg == "|")
+        .map(|stage| match stage {
+            [app] => Some(*app),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((apps, redirect))
+}
+
+/// Parse `[-<signal number>] <pid>` of `kill`
+fn parse_kill(args: &[&str]) -> Option<(u16, Signal)> {
+    let (sig, pid) = match args {
+        [pid] => (Signal::Term, pid),
+        [sig, pid] => {
+            let sig = sig.strip_prefix('-')?.parse::<usize>().ok()?;
+            (Signal::try_from(sig).ok()?, pid)
+        }
+        _ => return None,
+    };
+
+    Some((pid.parse().ok()?, sig))
+}
+
+/// Report the background apps exited since the last prompt
+fn reap_jobs() {
+    if !CHILD_EXITED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    while let Some((pid, ret)) = sys_waitpid(None, WaitFlags::NOHANG) {
+        if pid == 0 {
+            break;
+        }
+        println!("[{}] exit with code {}", pid, ret);
+    }
+}
+
+/// Run apps connected by pipes, writing the stdout of the last one to
+/// `redirect` if given, and wait for all of them unless in `background`
+fn run(apps: &[&str], redirect: Option<(&str, bool)>, background: bool){
+    for name in apps {
+        if !fs::metadata(&format!("/app/{}", name)).is_some_and(|stat| stat.is_file()) {
+            println!("run: {}: no such app", name);
+            return;
+        }
+    }
+
+    let output = match redirect {
+        Some((file_path, append)) => {
+            let file = if append { fs::File::append(file_path) } else { fs::File::create(file_path) };
+            match file {
+                Some(file) => Some(file),
+                None => {
+                    println!("run: cannot open {}", file_path);
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+
+    // children inherit our descriptors, so stdin and stdout
+    // are swapped around each spawn and restored at the end
+    let (Some(stdin), Some(stdout)) = (sys_dup(0), sys_dup(1)) else {
+        println!("run: cannot save stdin and stdout");
+        return;
+    };
+    let mut input = None;
+    let mut pids = Vec::new();
+    let mut broken = None;
+
+    for (idx, name) in apps.iter().enumerate() {
+        if let Some(fd) = input.take() {
+            sys_dup2(fd, 0);
+            sys_close(fd);
+        }
+
+        if idx + 1 < apps.len() {
+            // the read end stays out of this stage until the next one takes it
+            let Some((read, write)) = sys_pipe(OpenFlags::CLOEXEC) else {
+                broken = Some(name);
+                break;
+            };
+            sys_dup2(write, 1);
+            sys_close(write);
+            input = Some(read);
+        } else {
+            sys_dup2(output.as_ref().map_or(stdout, |file| file.fd()), 1);
+        }
+
+        let pid = sys_spawn(&format!("/app/{}", name));
+        // the apps form one group led by the first, taking Ctrl-C from the console
+        if pid != 0 {
+            let pgid = pids.iter().map(|&(_, pid)| pid).find(|&pid| pid != 0).unwrap_or(pid);
+            sys_setpgid(pid, pgid);
+            if !background {
+                sys_set_foreground(pgid);
+            }
+        }
+        pids.push((name, pid));
+    }
+
+    sys_dup2(stdin, 0);
+    sys_dup2(stdout, 1);
+    sys_close(stdin);
+    sys_close(stdout);
+    drop(output);
+
+    if let Some(name) = broken {
+        println!("run: {}: cannot create a pipe", name);
+    }
+    for (name, pid) in pids {
+        if pid == 0 {
+            println!("run: {}: failed to spawn", name);
+            continue;
+        }
+        if background {
+            println!("[{}] {}", pid, name);
+            continue;
+        }
+
+        let ret = sys_wait_pid(pid);
+        println!("{} exit with code {}", name, ret);
+    }
+    if !background {
+        sys_set_foreground(0);
+    }
 }
 
 pub fn sleep(millisecs: u64) {
@@ -83,6 +83,37 @@ pub fn map_range(
     Ok(Page::range(range_start, range_end))
 }
 
+/// Map given frames to a range of memory
+///
+/// the frames stay owned by the caller, e.g. shared by several page tables
+pub fn map_frames(
+    addr: u64,
+    frames: &[PhysFrame],
+    page_table: &mut impl Mapper<Size4KiB>,
+    frame_allocator: &mut impl FrameAllocator<Size4KiB>,
+    flags: PageTableFlags,
+) -> Result<PageRange, MapToError<Size4KiB>> {
+    let range_start = Page::containing_address(VirtAddr::new(addr));
+    let range_end = range_start + frames.len() as u64;
+
+    trace!(
+        "Map Frames: {:?}({})",
+        Page::range(range_start, range_end),
+        frames.len()
+    );
+
+    let flags = flags | PageTableFlags::PRESENT;
+    for (page, frame) in Page::range(range_start, range_end).zip(frames) {
+        unsafe {
+            page_table
+                .map_to(page, *frame, flags, frame_allocator)?
+                .flush();
+        }
+    }
+
+    Ok(Page::range(range_start, range_end))
+}
+
 /// Load & Map ELF file
 ///
 /// load segments in ELF file to new frames and set page table
@@ -258,6 +289,27 @@ pub fn unmap_range(
     Ok(())
 }
 
+/// Unmap a range of memory without freeing its frames
+pub fn unmap_pages(
+    addr: u64,
+    count: u64,
+    page_table: &mut impl Mapper<Size4KiB>,
+) -> Result<(), UnmapError> {
+    let range_start = Page::containing_address(VirtAddr::new(addr));
+    let range_end = range_start + count;
+    trace!(
+        "Unmap Pages: {:?}({})",
+        Page::range(range_start, range_end),
+        count
+    );
+    for page in Page::range(range_start, range_end) {
+        let (_, flush) = page_table.unmap(page)?;
+        flush.flush();
+    }
+
+    Ok(())
+}
+
 /// Clone a range of memory
 ///
 /// - `src_addr`: the address of the source memory
@@ -144,7 +144,15 @@ impl AtaBus {
             self.lba_low.write(bytes[0]);
             self.lba_mid.write(bytes[1]);
             self.lba_high.write(bytes[2]);
-            self.drive.write(0xE0 | (drive << 4) | ((bytes[3] & 0x0F) << 4));
+            self.drive.write(0xE0 | (drive << 4) | (bytes[3] & 0x0F));
+        }
+
+        // a floating bus reads 0xFF, there is no drive to answer the command
+        if self.status().bits() == 0xFF {
+            return Err(storage::DeviceError::UnknownDevice.into());
+        }
+
+        unsafe {
             self.command.write(cmd as u8);
         }
 
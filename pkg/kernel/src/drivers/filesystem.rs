@@ -1,40 +1,416 @@
 use super::ata::*;
 use alloc::boxed::Box;
+use alloc::collections::VecDeque;
 use alloc::format;
 use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
 // use chrono::DateTime;
+use storage::ext2::Ext2;
 use storage::fat16::Fat16;
+use storage::fat32::Fat32;
+use storage::gpt::*;
 use storage::mbr::*;
+use storage::tmpfs::TmpFs;
 use storage::*;
+use syscall_def::{Dirent, FileKind, OpenFlags, Stat};
 
-pub static ROOTFS: spin::Once<Mount> = spin::Once::new();
+use crate::pipe;
+use crate::resource::Resource;
 
-pub fn get_rootfs() -> &'static Mount {
-    ROOTFS.get().unwrap()
+pub static ROOTFS: MountTable = MountTable::new();
+
+pub fn get_rootfs() -> &'static MountTable {
+    &ROOTFS
 }
 
 pub fn init() {
-    info!("Opening disk device...");
+    info!("Probing disk devices...");
 
-    let drive = AtaDrive::open(0, 0).expect("Failed to open disk device");
+    let mut volumes = Vec::new();
 
-    // only get the first partition
-    let part = MbrTable::parse(drive)
-        .expect("Failed to parse MBR")
-        .partitions()
-        .expect("Failed to get partitions")
-        .remove(0);
+    for bus in 0..BUSES.len() as u8 {
+        for drive in 0..2 {
+            if let Some(drive) = AtaDrive::open(bus, drive) {
+                volumes.extend(probe_drive(drive));
+            }
+        }
+    }
 
     info!("Mounting filesystem...");
 
-    ROOTFS.call_once(|| Mount::new(Box::new(Fat16::new(part)), "/".into()));
+    // the first volume of the first drive is the root filesystem
+    let mut volumes = volumes.into_iter();
+    let (name, root) = volumes.next().expect("No root filesystem found");
 
-    trace!("Root filesystem: {:#?}", ROOTFS.get().unwrap());
+    ROOTFS.mount(root, "/").expect("Failed to mount root filesystem");
+    info!("Mounted {} at /", name);
+
+    ROOTFS
+        .mount(Box::new(TmpFs::new()), "/tmp")
+        .expect("Failed to mount tmpfs");
+
+    for (name, fs) in volumes {
+        let target = format!("/mnt/{}", name);
+        match ROOTFS.mount(fs, &target) {
+            Ok(()) => info!("Mounted {} at {}", name, target),
+            Err(e) => warn!("Failed to mount {} at {}: {:?}", name, target, e),
+        }
+    }
+
+    trace!("Mount table: {:#?}", ROOTFS);
 
     info!("Initialized Filesystem.");
 }
 
+/// Open every recognised filesystem on the drive, named like `hda2`
+fn probe_drive(drive: AtaDrive) -> Vec<(String, Box<dyn FileSystem>)> {
+    let name = drive_name(drive.bus, drive.drive);
+    let mut volumes = Vec::new();
+
+    for (number, part) in partitions_of(drive) {
+        let name = match number {
+            Some(number) => format!("{}{}", name, number),
+            None => name.clone(),
+        };
+
+        match open_fs(part) {
+            Some(fs) => volumes.push((name, fs)),
+            None => info!("{}: unrecognised filesystem", name),
+        }
+    }
+
+    volumes
+}
+
+/// List the partitions of the drive with their numbers
+///
+/// Primary partitions are numbered by their slot in the MBR, from 1 to 4,
+/// logical partitions follow from 5 in the order of the EBR chain,
+/// GPT partitions are numbered by their slot in the entry array, from 1.
+/// A drive without any partition is used as a whole, numbered `None`.
+fn partitions_of(drive: AtaDrive) -> Vec<(Option<usize>, Partition<AtaDrive, Block512>)> {
+    let blocks = drive.block_count().unwrap_or(0);
+    let whole = Partition::new(drive.clone(), 0, blocks);
+
+    // the boot sector of a filesystem is not a partition table
+    if is_volume(&whole) {
+        return vec![(None, whole)];
+    }
+
+    if GptTable::<AtaDrive, Block512>::is_gpt(&drive) {
+        return gpt_partitions_of(drive);
+    }
+
+    let table = match MbrTable::parse(drive.clone()) {
+        Ok(table) => table,
+        Err(e) => {
+            warn!("Failed to parse MBR of {}: {:?}", drive, e);
+            return Vec::new();
+        }
+    };
+
+    let mut parts = Vec::new();
+
+    for (idx, entry) in table.entries().iter().enumerate() {
+        if entry.is_empty() {
+            continue;
+        }
+
+        trace!(
+            "{}{}: type {:#04x}, {} sectors",
+            drive_name(drive.bus, drive.drive),
+            idx + 1,
+            entry.partition_type(),
+            entry.total_lba()
+        );
+
+        if entry.is_extended() {
+            continue;
+        }
+
+        let part = Partition::new(
+            drive.clone(),
+            entry.begin_lba() as usize,
+            entry.total_lba() as usize,
+        );
+        parts.push((Some(idx + 1), part));
+    }
+
+    for (idx, entry) in table.logical_entries().iter().enumerate() {
+        // the EBR chain walk only keeps partitions within a 32-bit LBA
+        let Some(begin) = entry.begin_lba() else {
+            continue;
+        };
+
+        trace!(
+            "{}{}: type {:#04x}, {} sectors",
+            drive_name(drive.bus, drive.drive),
+            idx + 5,
+            entry.entry.partition_type(),
+            entry.total_lba()
+        );
+
+        let part = Partition::new(drive.clone(), begin as usize, entry.total_lba() as usize);
+        parts.push((Some(idx + 5), part));
+    }
+
+    parts
+}
+
+fn gpt_partitions_of(drive: AtaDrive) -> Vec<(Option<usize>, Partition<AtaDrive, Block512>)> {
+    let table = match GptTable::parse(drive.clone()) {
+        Ok(table) => table,
+        Err(e) => {
+            warn!("Failed to parse GPT of {}: {:?}", drive, e);
+            return Vec::new();
+        }
+    };
+
+    table
+        .entries()
+        .iter()
+        .map(|(idx, entry)| {
+            trace!(
+                "{}{}: {:?} type {}, {} sectors",
+                drive_name(drive.bus, drive.drive),
+                idx + 1,
+                entry.name(),
+                entry.type_guid(),
+                entry.total_lba()
+            );
+
+            let part = Partition::new(
+                drive.clone(),
+                entry.first_lba() as usize,
+                entry.total_lba() as usize,
+            );
+            (Some(idx + 1), part)
+        })
+        .collect()
+}
+
+/// Name a drive like Linux does, `hda` to `hdd`
+fn drive_name(bus: u8, drive: u8) -> String {
+    format!("hd{}", (b'a' + bus * 2 + drive) as char)
+}
+
+/// Mount the filesystem of `source` at `target`
+///
+/// `source` is either `tmpfs`, a partition like `hda1`
+/// or a drive without partitions like `hdb`
+pub fn mount(source: &str, target: &str) -> Result<()> {
+    let fs = open_source(source)?;
+    ROOTFS.mount(fs, target)?;
+
+    info!("Mounted {} at {}", source, target);
+    Ok(())
+}
+
+/// Unmount the filesystem at `target`
+pub fn umount(target: &str) -> Result<()> {
+    ROOTFS.umount(target)?;
+
+    info!("Unmounted {}", target);
+    Ok(())
+}
+
+/// Open the file at `path` as `open(2)` does
+///
+/// Without `CREATE` the file must exist, `TRUNCATE` and `APPEND`
+/// only make sense together with `WRITE`.
+pub fn open(path: &str, flags: OpenFlags) -> Result<Resource> {
+    if flags.contains(OpenFlags::DIRECTORY) {
+        return open_dir(path).map(Resource::Dir);
+    }
+
+    let writable = flags.contains(OpenFlags::WRITE);
+
+    if !writable && (flags.contains(OpenFlags::TRUNCATE) || flags.contains(OpenFlags::APPEND)) {
+        return Err(FsError::InvalidOperation);
+    }
+
+    let fs = get_rootfs();
+
+    if !fs.exists(path)? {
+        if !flags.contains(OpenFlags::CREATE) {
+            return Err(FsError::FileNotFound);
+        }
+        if !writable {
+            return Err(FsError::InvalidOperation);
+        }
+    } else {
+        let meta = fs.metadata(path)?;
+        if meta.is_dir() {
+            return Err(FsError::NotAFile);
+        }
+        if meta.is_fifo() {
+            return open_fifo(path, flags);
+        }
+    }
+
+    let handle = if flags.contains(OpenFlags::APPEND) {
+        fs.append_file(path)
+    } else if flags.contains(OpenFlags::TRUNCATE) || !fs.exists(path)? {
+        fs.create_file(path)
+    } else {
+        fs.open_file(path)
+    }?;
+
+    Ok(Resource::File(handle, flags))
+}
+
+/// List the directory at `path` for `getdents`
+fn open_dir(path: &str) -> Result<VecDeque<Metadata>> {
+    Ok(get_rootfs().read_dir(path)?.collect())
+}
+
+/// Open one end of the FIFO at `path`, which must not be both
+fn open_fifo(path: &str, flags: OpenFlags) -> Result<Resource> {
+    let write = match (flags.contains(OpenFlags::READ), flags.contains(OpenFlags::WRITE)) {
+        (true, false) => false,
+        (false, true) => true,
+        _ => return Err(FsError::InvalidOperation),
+    };
+
+    Ok(Resource::Pipe(pipe::open_fifo(&normalize_path(path), write)))
+}
+
+/// Create a named FIFO at `path`
+pub fn mkfifo(path: &str) -> Result<()> {
+    get_rootfs().create_fifo(path)
+}
+
+/// Remove the file at `path`, a FIFO there can not be opened by it anymore
+pub fn remove_file(path: &str) -> Result<()> {
+    get_rootfs().remove_file(path)?;
+    pipe::remove_fifo(&normalize_path(path));
+    Ok(())
+}
+
+/// Move the file at `src` to `dst`, a FIFO in use keeps its pipe
+pub fn move_file(src: &str, dst: &str) -> Result<()> {
+    get_rootfs().move_file(src, dst)?;
+    pipe::move_fifo(&normalize_path(src), &normalize_path(dst));
+    Ok(())
+}
+
+/// Get the metadata of the file or directory at `path`
+pub fn stat(path: &str) -> Result<Stat> {
+    Ok(stat_of(&get_rootfs().metadata(path)?))
+}
+
+fn kind_of(meta: &Metadata) -> FileKind {
+    match meta.entry_type {
+        FileType::File => FileKind::File,
+        FileType::Directory => FileKind::Directory,
+        FileType::Symlink => FileKind::Symlink,
+        FileType::Fifo => FileKind::Fifo,
+    }
+}
+
+fn timestamp(time: Option<FsTime>) -> i64 {
+    time.map_or(Dirent::NO_TIME, |time| time.timestamp())
+}
+
+pub fn dirent_of(meta: &Metadata) -> Dirent {
+    Dirent::new(
+        kind_of(meta),
+        meta.len as u64,
+        timestamp(meta.created),
+        timestamp(meta.modified),
+        timestamp(meta.accessed),
+    )
+}
+
+pub fn stat_of(meta: &Metadata) -> Stat {
+    let posix = meta.posix.unwrap_or(PosixMetadata {
+        mode: 0,
+        uid: 0,
+        gid: 0,
+        links: 0,
+    });
+
+    Stat {
+        kind: kind_of(meta),
+        mode: posix.mode,
+        links: posix.links,
+        uid: posix.uid,
+        gid: posix.gid,
+        size: meta.len as u64,
+        created: timestamp(meta.created),
+        modified: timestamp(meta.modified),
+        accessed: timestamp(meta.accessed),
+    }
+}
+
+fn open_source(source: &str) -> Result<Box<dyn FileSystem>> {
+    if source == "tmpfs" {
+        return Ok(Box::new(TmpFs::new()));
+    }
+
+    let (bus, drive, number) =
+        parse_device_name(source).ok_or_else(|| FsError::InvalidPath(source.into()))?;
+
+    let drive = AtaDrive::open(bus, drive).ok_or(DeviceError::UnknownDevice)?;
+    let part = partitions_of(drive)
+        .into_iter()
+        .find(|(n, _)| *n == number)
+        .map(|(_, part)| part)
+        .ok_or(FsError::FileNotFound)?;
+
+    open_fs(part).ok_or(FsError::NotSupported)
+}
+
+/// Parse names like `hdb2` into (bus, drive, partition number)
+///
+/// `hda` and `hdb` are the drives on the primary bus,
+/// `hdc` and `hdd` are on the secondary one.
+fn parse_device_name(name: &str) -> Option<(u8, u8, Option<usize>)> {
+    let rest = name.strip_prefix("hd")?;
+    let letter = rest.bytes().next()?;
+
+    if !(b'a'..=b'd').contains(&letter) {
+        return None;
+    }
+
+    let number = match &rest[1..] {
+        "" => None,
+        number => Some(number.parse().ok().filter(|&n| n > 0)?),
+    };
+    let drive = letter - b'a';
+
+    Some((drive / 2, drive % 2, number))
+}
+
+/// Check whether the partition starts with a known filesystem
+fn is_volume(part: &Partition<AtaDrive, Block512>) -> bool {
+    let mut block = Block512::default();
+
+    Ext2::probe(part)
+        || (part.read_block(0, &mut block).is_ok() && FatType::detect(block.as_ref()).is_some())
+}
+
+/// Pick the filesystem driver by the content of the partition
+fn open_fs(part: Partition<AtaDrive, Block512>) -> Option<Box<dyn FileSystem>> {
+    if Ext2::probe(&part) {
+        info!("Detected filesystem: Ext2");
+        return Some(Box::new(Ext2::new(part)));
+    }
+
+    // FAT variants are told apart by the cluster count in the boot sector
+    let mut block = Block512::default();
+    part.read_block(0, &mut block).ok()?;
+
+    let fat_type = FatType::detect(block.as_ref())?;
+    info!("Detected filesystem: {:?}", fat_type);
+
+    Some(match fat_type {
+        FatType::Fat12 | FatType::Fat16 => Box::new(Fat16::new(part)),
+        FatType::Fat32 => Box::new(Fat32::new(part)),
+    })
+}
+
 pub fn ls(root_path: &str) {
     println!("{:12} {:12} {:20}", "Name", "Size", "Last Modified");
     let iter = match get_rootfs().read_dir(root_path) {
@@ -1,5 +1,10 @@
+use alloc::collections::VecDeque;
+use core::sync::atomic::{AtomicU16, Ordering};
 use crossbeam_queue::ArrayQueue;
 use lazy_static::lazy_static;
+use spin::Mutex;
+
+use crate::proc::{manager::get_process_manager, ProcessId};
 
 type Key = u8; // 将 Key 类型从 u8 更改为 char
 
@@ -7,11 +12,47 @@ lazy_static! {
     static ref INPUT_BUF: ArrayQueue<Key> = ArrayQueue::new(128);
 }
 
+/// The processes reading the console while there is no key
+static WAITERS: Mutex<VecDeque<ProcessId>> = Mutex::new(VecDeque::new());
+
+/// The process group signaled by control characters, 0 for none
+static FOREGROUND: AtomicU16 = AtomicU16::new(0);
+
+#[inline]
+pub fn foreground() -> u16 {
+    FOREGROUND.load(Ordering::Relaxed)
+}
+
+#[inline]
+pub fn set_foreground(pgid: u16) {
+    FOREGROUND.store(pgid, Ordering::Relaxed);
+}
+
 #[inline]
 pub fn push_key(key: Key) {
     if INPUT_BUF.push(key).is_err() {
         warn!("Input buffer is full. Dropping key '{:?}'", key);
     }
+
+    let manager = get_process_manager();
+    for pid in WAITERS.lock().drain(..) {
+        manager.wake_up(pid, None);
+    }
+}
+
+/// Drop the reaped `pid` from the readers waiting for a key
+pub fn forget(pid: ProcessId) {
+    WAITERS.lock().retain(|&waiter| waiter != pid);
+}
+
+/// Check whether a read has to wait for a key,
+/// if so `pid` is woken up by the next one
+pub fn wait_key(pid: ProcessId) -> bool {
+    let wait = INPUT_BUF.is_empty();
+    if wait {
+        WAITERS.lock().push_back(pid);
+    }
+    wait
 }
 
 #[inline]
@@ -1,6 +1,9 @@
 use super::consts::*;
 use x86_64::structures::idt::InterruptDescriptorTable;
+use crate::drivers::input;
+use crate::proc::{self, ProcessId};
 use crate::serial::get_serial;
+use syscall_def::Signal;
 use x86_64::structures::idt::InterruptStackFrame;
 
 pub unsafe fn register_idt(idt: &mut InterruptDescriptorTable) {
@@ -19,6 +22,23 @@ fn receive() {
     // println!("keyboard interrupt");
     let mut serial_port = get_serial().expect("get serial failed");
     while let Some(byte) = serial_port.receive() {
-        crate::drivers::input::push_key(byte);
+        if !signal_foreground(byte) {
+            input::push_key(byte);
+        }
+    }
+}
+
+/// Send the signal of a control character to the foreground process group,
+/// returns `false` for other keys, or if there is no one to signal
+fn signal_foreground(byte: u8) -> bool {
+    let sig = match byte {
+        0x03 => Signal::Int,  // Ctrl-C
+        0x1c => Signal::Quit, // Ctrl-\
+        _ => return false,
+    };
+
+    match input::foreground() {
+        0 => false,
+        pgid => proc::kill_group(ProcessId(pgid), sig),
     }
 }
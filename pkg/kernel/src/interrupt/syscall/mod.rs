@@ -37,6 +37,8 @@ pub struct SyscallArgs {
     pub arg0: usize,
     pub arg1: usize,
     pub arg2: usize,
+    pub arg3: usize,
+    pub arg4: usize,
 }
 
 pub fn dispatcher(context: &mut ProcessContext) {
@@ -45,6 +47,8 @@ pub fn dispatcher(context: &mut ProcessContext) {
         context.regs.rdi,
         context.regs.rsi,
         context.regs.rdx,
+        context.regs.r10,
+        context.regs.r8,
     );
 
     // NOTE: you may want to trace syscall arguments
@@ -53,14 +57,39 @@ pub fn dispatcher(context: &mut ProcessContext) {
     match args.syscall {
         // fd: arg0 as u8, buf: &[u8] (ptr: arg1 as *const u8, len: arg2)
         Syscall::Read => { /* FIXME: read from fd & return length */
-            let ret = sys_read(&args);
-            context.set_rax(ret);
+            sys_read(&args, context);
         },
         // fd: arg0 as u8, buf: &[u8] (ptr: arg1 as *const u8, len: arg2)
         Syscall::Write => { /* FIXME: write to fd & return length */
-            let ret = sys_write(&args);
-            context.set_rax(ret);
+            sys_write(&args, context);
         },
+        // path: &str (arg0 as *const u8, arg1 as len), flags: arg2 -> fd: isize
+        Syscall::Open => context.set_rax(sys_open(&args)),
+        // fd: arg0 as u8 -> ret: isize
+        Syscall::Close => context.set_rax(sys_close(&args)),
+        // path: &str (arg0 as *const u8, arg1 as len), stat: arg2 as *mut Stat -> ret: isize
+        Syscall::FileStat => context.set_rax(sys_file_stat(&args)),
+        // fd: arg0 as u8, stat: arg1 as *mut Stat -> ret: isize
+        Syscall::FStat => context.set_rax(sys_fstat(&args)),
+        // fd: arg0 as u8, offset: arg1 as isize, whence: arg2 -> offset: isize
+        Syscall::Seek => context.set_rax(sys_seek(&args)),
+        // fd: arg0 as u8, buf: &mut [u8] (ptr: arg1 as *mut u8, len: arg2),
+        // offset: arg3 -> length: isize
+        Syscall::PRead => context.set_rax(sys_pread(&args)),
+
+        // fds: arg0 as *mut [u8; 2], flags: arg1 as OpenFlags -> ret: isize
+        Syscall::Pipe => context.set_rax(sys_pipe(&args)),
+        // path: &str (arg0 as *const u8, arg1 as len) -> ret: isize
+        Syscall::MkFifo => context.set_rax(sys_mkfifo(&args)),
+        // path: &str (arg0 as *const u8, arg1 as len) -> ret: isize
+        Syscall::Unlink => context.set_rax(sys_unlink(&args)),
+        // src: &str (arg0 as *const u8, arg1 as len),
+        // dst: &str (arg2 as *const u8, arg3 as len) -> ret: isize
+        Syscall::Rename => context.set_rax(sys_rename(&args)),
+        // fd: arg0 as u8 -> fd: isize
+        Syscall::Dup => context.set_rax(sys_dup(&args)),
+        // old_fd: arg0 as u8, new_fd: arg1 as u8 -> fd: isize
+        Syscall::Dup2 => context.set_rax(sys_dup2(&args)),
 
         // None -> pid: u16
         Syscall::GetPid => { /* FIXME: get current pid */ 
@@ -82,21 +111,46 @@ pub fn dispatcher(context: &mut ProcessContext) {
         Syscall::Exit => { /* FIXME: exit process with retcode */
             exit_process(&args, context);
         },
-        // pid: arg0 as u16 -> status: isize
-        Syscall::WaitPid => { /* FIXME: check if the process is running or get retcode */
-            let pid = ProcessId(args.arg0 as u16);
-            // let manager = get_process_manager();
-            // let ret = if let Some(ret) = manager.check_proc(&pid) {
-            //     ret
-            // }else{
-            //     -1
-            // };
-            // context.set_rax(ret as usize);
-            wait_pid(pid, context);
-        },
-
-        // op: u8, key: u32, val: usize -> ret: any
+        // pid: arg0 as isize (-1 for any child), flags: arg1,
+        // status: arg2 as *mut isize -> pid: isize
+        Syscall::WaitPid => sys_wait_pid(&args, context),
+        // pid: arg0 as u16, sig: arg1 -> ret: isize
+        Syscall::Kill => context.set_rax(sys_kill(&args)),
+        // pid: arg0 as u16, pgid: arg1 as u16 -> ret: isize
+        Syscall::SetPgid => context.set_rax(sys_setpgid(&args)),
+        // pgid: arg0 as u16, 0 for none -> ret: isize
+        Syscall::SetForeground => context.set_rax(sys_set_foreground(&args)),
+        // sig: arg0, handler: arg1, restorer: arg2 -> ret: isize
+        Syscall::SigAction => context.set_rax(sys_sigaction(&args)),
+        // how: arg0, set: arg1 as u64 -> old: u64
+        Syscall::SigProcMask => context.set_rax(sys_sigprocmask(&args)),
+        // None, returns to the context before the handler
+        Syscall::SigReturn => sigreturn(context),
+
+        // source: &str (arg0 as *const u8, arg1 as len),
+        // target: &str (arg2 as *const u8, arg3 as len) -> ret: isize
+        Syscall::Mount => context.set_rax(sys_mount(&args)),
+        // target: &str (arg0 as *const u8, arg1 as len) -> ret: isize
+        Syscall::Umount => context.set_rax(sys_umount(&args)),
+
+        // fd: arg0 as u8, buf: &mut [u8] (ptr: arg1 as *mut u8, len: arg2) -> length: isize
+        Syscall::GetDents => context.set_rax(sys_getdents(&args)),
+
+        // op: u8, key: u32, val: usize, flags: arg3 -> ret: any
         Syscall::Sem => sys_sem(&args, context),
+        // op: u8, key: u32, size: usize -> ret: any
+        Syscall::Shm => context.set_rax(sys_shm(&args)),
+        // key: arg0 as u32, flags: arg1 -> ret: isize
+        Syscall::MsgGet => context.set_rax(sys_msgget(&args)),
+        // key: arg0 as u32, msg: arg1 as *const u8, len: arg2, flags: arg3 -> ret: isize
+        Syscall::MsgSnd => sys_msgsnd(&args, context),
+        // key: arg0 as u32, msg: arg1 as *mut u8, len: arg2, type: arg3 as isize,
+        // flags: arg4 -> length: isize
+        Syscall::MsgRcv => sys_msgrcv(&args, context),
+        // key: arg0 as u32, cmd: arg1 -> ret: isize
+        Syscall::MsgCtl => context.set_rax(sys_msgctl(&args)),
+        // addr: arg0 as *const u32, op: arg1, val: arg2 -> ret: isize
+        Syscall::Futex => sys_futex(&args, context),
         
         // None -> Time
         Syscall::Time => {
@@ -145,15 +199,26 @@ pub fn dispatcher(context: &mut ProcessContext) {
         // Unknown
         Syscall::Unknown => warn!("Unhandled syscall: {:x?}", context.regs.rax),
     }
+
+    handle_signals(context);
 }
 
 impl SyscallArgs {
-    pub fn new(syscall: Syscall, arg0: usize, arg1: usize, arg2: usize) -> Self {
+    pub fn new(
+        syscall: Syscall,
+        arg0: usize,
+        arg1: usize,
+        arg2: usize,
+        arg3: usize,
+        arg4: usize,
+    ) -> Self {
         Self {
             syscall,
             arg0,
             arg1,
             arg2,
+            arg3,
+            arg4,
         }
     }
 }
@@ -162,11 +227,13 @@ impl core::fmt::Display for SyscallArgs {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(
             f,
-            "SYSCALL: {:<10} (0x{:016x}, 0x{:016x}, 0x{:016x})",
+            "SYSCALL: {:<10} (0x{:016x}, 0x{:016x}, 0x{:016x}, 0x{:016x}, 0x{:016x})",
             format!("{:?}", self.syscall),
             self.arg0,
             self.arg1,
-            self.arg2
+            self.arg2,
+            self.arg3,
+            self.arg4
         )
     }
 }
@@ -1,6 +1,12 @@
 use core::alloc::Layout;
 
-use crate::clock::get_timer_for_sure;
+use storage::SeekFrom;
+use syscall_def::{
+    FutexOp, MsgCmd, MsgFlags, OpenFlags, SemFlags, SigHow, Signal, Stat, WaitFlags, Whence,
+};
+use x86_64::VirtAddr;
+
+use crate::filesystem;
 use crate::proc;
 use crate::proc::*;
 
@@ -26,7 +32,7 @@ pub fn spawn_process(args: &SyscallArgs) -> usize {
     }
 }
 
-pub fn sys_write(args: &SyscallArgs) -> usize {
+pub fn sys_write(args: &SyscallArgs, context: &mut ProcessContext) {
     // FIXME: get buffer and fd by args
     //       - core::slice::from_raw_parts
     // FIXME: call proc::write -> isize
@@ -36,18 +42,167 @@ pub fn sys_write(args: &SyscallArgs) -> usize {
     let len = args.arg2 as usize;
     unsafe{
         let buf = core::slice::from_raw_parts(ptr, len);
-        proc::write(fd, buf) as usize
+        proc::write(fd, buf, context)
     }
 }
 
-pub fn sys_read(args: &SyscallArgs) -> usize {
+pub fn sys_read(args: &SyscallArgs, context: &mut ProcessContext) {
     // FIXME: just like sys_write
     let fd = args.arg0 as u8;
     let ptr = args.arg1 as *mut u8;
     let len = args.arg2 as usize;
     unsafe{
-        let mut buf = core::slice::from_raw_parts_mut(ptr, len);
-        proc::read(fd, &mut buf) as usize
+        let buf = core::slice::from_raw_parts_mut(ptr, len);
+        proc::read(fd, buf, context)
+    }
+}
+
+pub fn sys_pipe(args: &SyscallArgs) -> usize {
+    match proc::pipe(OpenFlags(args.arg1)) {
+        Some((read, write)) => {
+            let fds = args.arg0 as *mut [u8; 2];
+            unsafe { fds.write_unaligned([read, write]) };
+            0
+        }
+        None => usize::MAX,
+    }
+}
+
+pub fn sys_open(args: &SyscallArgs) -> usize {
+    let path = unsafe {
+        let path = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        core::str::from_utf8_unchecked(path)
+    };
+    let flags = OpenFlags(args.arg2);
+
+    match filesystem::open(path, flags) {
+        Ok(res) => proc::open(res).map_or(usize::MAX, |fd| fd as usize),
+        Err(e) => {
+            debug!("Failed to open {}: {:?}", path, e);
+            usize::MAX
+        }
+    }
+}
+
+pub fn sys_unlink(args: &SyscallArgs) -> usize {
+    let path = unsafe {
+        let path = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        core::str::from_utf8_unchecked(path)
+    };
+
+    match filesystem::remove_file(path) {
+        Ok(()) => 0,
+        Err(e) => {
+            debug!("Failed to remove {}: {:?}", path, e);
+            usize::MAX
+        }
+    }
+}
+
+pub fn sys_rename(args: &SyscallArgs) -> usize {
+    let (src, dst) = unsafe {
+        let src = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        let dst = core::slice::from_raw_parts(args.arg2 as *const u8, args.arg3);
+        (core::str::from_utf8_unchecked(src), core::str::from_utf8_unchecked(dst))
+    };
+
+    match filesystem::move_file(src, dst) {
+        Ok(()) => 0,
+        Err(e) => {
+            debug!("Failed to move {} to {}: {:?}", src, dst, e);
+            usize::MAX
+        }
+    }
+}
+
+pub fn sys_mkfifo(args: &SyscallArgs) -> usize {
+    let path = unsafe {
+        let path = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        core::str::from_utf8_unchecked(path)
+    };
+
+    match filesystem::mkfifo(path) {
+        Ok(()) => 0,
+        Err(e) => {
+            debug!("Failed to create fifo {}: {:?}", path, e);
+            usize::MAX
+        }
+    }
+}
+
+pub fn sys_close(args: &SyscallArgs) -> usize {
+    if proc::close(args.arg0 as u8) {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_dup(args: &SyscallArgs) -> usize {
+    proc::dup(args.arg0 as u8).map_or(usize::MAX, |fd| fd as usize)
+}
+
+pub fn sys_dup2(args: &SyscallArgs) -> usize {
+    proc::dup2(args.arg0 as u8, args.arg1 as u8).map_or(usize::MAX, |fd| fd as usize)
+}
+
+pub fn sys_file_stat(args: &SyscallArgs) -> usize {
+    let path = unsafe {
+        let path = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        core::str::from_utf8_unchecked(path)
+    };
+
+    match filesystem::stat(path) {
+        Ok(stat) => {
+            unsafe { core::ptr::write_unaligned(args.arg2 as *mut Stat, stat) };
+            0
+        }
+        Err(_) => usize::MAX,
+    }
+}
+
+pub fn sys_fstat(args: &SyscallArgs) -> usize {
+    match proc::fstat(args.arg0 as u8) {
+        Some(stat) => {
+            unsafe { core::ptr::write_unaligned(args.arg1 as *mut Stat, stat) };
+            0
+        }
+        None => usize::MAX,
+    }
+}
+
+pub fn sys_seek(args: &SyscallArgs) -> usize {
+    let fd = args.arg0 as u8;
+    let offset = args.arg1 as isize;
+
+    let pos = match Whence::try_from(args.arg2) {
+        Ok(Whence::Start) if offset >= 0 => SeekFrom::Start(offset as usize),
+        Ok(Whence::Current) => SeekFrom::Current(offset),
+        Ok(Whence::End) => SeekFrom::End(offset),
+        _ => return usize::MAX,
+    };
+
+    proc::seek(fd, pos) as usize
+}
+
+pub fn sys_pread(args: &SyscallArgs) -> usize {
+    let fd = args.arg0 as u8;
+    let ptr = args.arg1 as *mut u8;
+    let len = args.arg2;
+    let offset = args.arg3;
+    unsafe {
+        let buf = core::slice::from_raw_parts_mut(ptr, len);
+        proc::pread(fd, buf, offset) as usize
+    }
+}
+
+pub fn sys_getdents(args: &SyscallArgs) -> usize {
+    let fd = args.arg0 as u8;
+    let ptr = args.arg1 as *mut u8;
+    let len = args.arg2;
+    unsafe {
+        let buf = core::slice::from_raw_parts_mut(ptr, len);
+        proc::getdents(fd, buf) as usize
     }
 }
 
@@ -96,9 +251,7 @@ pub fn sys_deallocate(args: &SyscallArgs) {
 }
 
 pub fn sys_time() -> usize {
-    let timer = get_timer_for_sure();
-    let time = timer.get_time();
-    time.nanosecond() as usize / 1000 + time.second() as usize * 1000 + time.minute() as usize * 60 * 1000 + time.hour() as usize * 3600000 + time.day() as usize * 24 * 3600000
+    crate::clock::now_ms() as usize
 }
 
 pub fn sys_fork(context: &mut ProcessContext){
@@ -107,10 +260,184 @@ pub fn sys_fork(context: &mut ProcessContext){
 
 pub fn sys_sem(args: &SyscallArgs, context: &mut ProcessContext) {
     match args.arg0 {
-        0 => context.set_rax(new_sem(args.arg1 as u32, args.arg2)),
+        0 => {
+            let flags = SemFlags(args.arg3);
+            context.set_rax(new_sem(args.arg1 as u32, args.arg2, flags.contains(SemFlags::SHARED)))
+        }
         1 => context.set_rax(remove_sem(args.arg1 as u32)),
         2 => sem_signal(args.arg1 as u32, context),
         3 => sem_wait(args.arg1 as u32, context),
+        4 => context.set_rax(open_sem(args.arg1 as u32)),
+        5 => context.set_rax(close_sem(args.arg1 as u32)),
+        6 => context.set_rax(sem_try_wait(args.arg1 as u32)),
+        7 => sem_timed_wait(args.arg1 as u32, args.arg2 as u64, context),
         _ => context.set_rax(usize::MAX),
     }
-}
\ No newline at end of file
+}
+
+pub fn sys_shm(args: &SyscallArgs) -> usize {
+    let key = args.arg1 as u32;
+    let ok = match args.arg0 {
+        0 => shm_create(key, args.arg2),
+        1 => shm_remove(key),
+        // the address of the segment, or null
+        2 => return shm_attach(key).map_or(0, |addr| addr.as_u64() as usize),
+        3 => shm_detach(key),
+        _ => false,
+    };
+
+    if ok {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_msgget(args: &SyscallArgs) -> usize {
+    let flags = MsgFlags(args.arg1);
+    if msg_get(args.arg0 as u32, flags.contains(MsgFlags::CREATE)) {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_msgctl(args: &SyscallArgs) -> usize {
+    let ok = match MsgCmd::try_from(args.arg1) {
+        Ok(MsgCmd::Remove) => msg_remove(args.arg0 as u32),
+        Err(_) => false,
+    };
+
+    if ok {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+/// The message is the type as `isize` followed by the text, like `struct msgbuf`
+pub fn sys_msgsnd(args: &SyscallArgs, context: &mut ProcessContext) {
+    let key = args.arg0 as u32;
+    let ptr = args.arg1 as *const u8;
+    let flags = MsgFlags(args.arg3);
+    unsafe {
+        let ty = core::ptr::read_unaligned(ptr as *const isize);
+        let text = core::slice::from_raw_parts(ptr.add(core::mem::size_of::<isize>()), args.arg2);
+        msg_send(key, ty, text, flags.contains(MsgFlags::NOWAIT), context);
+    }
+}
+
+pub fn sys_msgrcv(args: &SyscallArgs, context: &mut ProcessContext) {
+    let key = args.arg0 as u32;
+    let ptr = args.arg1 as *mut u8;
+    let ty = args.arg3 as isize;
+    let flags = MsgFlags(args.arg4);
+    unsafe {
+        let text = core::slice::from_raw_parts_mut(ptr.add(core::mem::size_of::<isize>()), args.arg2);
+        if let Some(ty) = msg_receive(key, ty, text, flags.contains(MsgFlags::NOWAIT), context) {
+            core::ptr::write_unaligned(ptr as *mut isize, ty);
+        }
+    }
+}
+
+pub fn sys_futex(args: &SyscallArgs, context: &mut ProcessContext) {
+    let addr = VirtAddr::new_truncate(args.arg0 as u64);
+    match FutexOp::try_from(args.arg1) {
+        Ok(FutexOp::Wait) => futex_wait(addr, args.arg2 as u32, context),
+        Ok(FutexOp::Wake) => context.set_rax(futex_wake(addr, args.arg2)),
+        Err(_) => context.set_rax(usize::MAX),
+    }
+}
+
+pub fn sys_wait_pid(args: &SyscallArgs, context: &mut ProcessContext) {
+    // -1 for any child
+    let pid = match args.arg0 as isize {
+        -1 => None,
+        pid => Some(ProcessId(pid as u16)),
+    };
+    let nohang = WaitFlags(args.arg1).contains(WaitFlags::NOHANG);
+    let status = unsafe { (args.arg2 as *mut isize).as_mut() };
+
+    wait_pid(pid, nohang, status, context);
+}
+
+pub fn sys_kill(args: &SyscallArgs) -> usize {
+    let Ok(sig) = Signal::try_from(args.arg1) else {
+        return usize::MAX;
+    };
+
+    if send_signal(ProcessId(args.arg0 as u16), sig) {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_setpgid(args: &SyscallArgs) -> usize {
+    if set_pgid(ProcessId(args.arg0 as u16), ProcessId(args.arg1 as u16)) {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_set_foreground(args: &SyscallArgs) -> usize {
+    if set_foreground(args.arg0 as u16) {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_sigaction(args: &SyscallArgs) -> usize {
+    let Ok(sig) = Signal::try_from(args.arg0) else {
+        return usize::MAX;
+    };
+
+    if sigaction(sig, SigAction::new(args.arg1, args.arg2)) {
+        0
+    } else {
+        usize::MAX
+    }
+}
+
+pub fn sys_sigprocmask(args: &SyscallArgs) -> usize {
+    match SigHow::try_from(args.arg0) {
+        Ok(how) => sigprocmask(how, args.arg1 as u64) as usize,
+        Err(_) => usize::MAX,
+    }
+}
+
+pub fn sys_mount(args: &SyscallArgs) -> usize {
+    let (source, target) = unsafe {
+        let source = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        let target = core::slice::from_raw_parts(args.arg2 as *const u8, args.arg3);
+        (
+            core::str::from_utf8_unchecked(source),
+            core::str::from_utf8_unchecked(target),
+        )
+    };
+
+    match filesystem::mount(source, target) {
+        Ok(()) => 0,
+        Err(e) => {
+            warn!("Failed to mount {} at {}: {:?}", source, target, e);
+            usize::MAX
+        }
+    }
+}
+
+pub fn sys_umount(args: &SyscallArgs) -> usize {
+    let target = unsafe {
+        let target = core::slice::from_raw_parts(args.arg0 as *const u8, args.arg1);
+        core::str::from_utf8_unchecked(target)
+    };
+
+    match filesystem::umount(target) {
+        Ok(()) => 0,
+        Err(e) => {
+            warn!("Failed to unmount {}: {:?}", target, e);
+            usize::MAX
+        }
+    }
+}
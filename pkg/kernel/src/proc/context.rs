@@ -32,6 +32,12 @@ impl ProcessContext {
         self.value.regs.rax = value;
     }
 
+    /// Rewind to the `int 0x80` so the syscall runs again once resumed
+    #[inline]
+    pub fn retry_syscall(&mut self) {
+        self.value.stack_frame.instruction_pointer -= 2u64;
+    }
+
     #[inline]
     pub fn save(&mut self, context: &ProcessContext) {
         self.value = context.as_ref().as_ptr().read();
@@ -1,11 +1,15 @@
-use alloc::{collections::BTreeMap, sync::Arc};
-use spin::RwLock;
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use spin::{Mutex, RwLock};
+use storage::SeekFrom;
+use syscall_def::Stat;
 use x86_64::structures::paging::{
     page::PageRange,
     Page,
 };
 
-use crate::resource::ResourceSet;
+use crate::resource::{Resource, ResourceSet};
+
+use super::sync::SemaphoreHandles;
 
 use super::*;
 
@@ -18,6 +22,12 @@ pub struct ProcessData {
     pub(super) stack_segment: Option<PageRange>,
 
     pub(super) resources: Arc<RwLock<ResourceSet>>,
+
+    /// Keys of the shared memory attached by this process
+    pub(super) shm: Vec<u32>,
+
+    /// Semaphores kept alive by this process, copied by `fork`
+    pub(super) semaphores: SemaphoreHandles,
 }
 
 impl Default for ProcessData {
@@ -25,7 +35,9 @@ impl Default for ProcessData {
         Self {
             env: Arc::new(RwLock::new(BTreeMap::new())),
             stack_segment: None,
-            resources: Arc::new(RwLock::new(ResourceSet::default()))
+            resources: Arc::new(RwLock::new(ResourceSet::default())),
+            shm: Vec::new(),
+            semaphores: SemaphoreHandles::default(),
         }
     }
 }
@@ -55,6 +67,46 @@ impl ProcessData {
         }
         return false;
     }
+    /// Use a copy of `resources` as the descriptor table
+    pub fn with_resources(mut self, resources: ResourceSet) -> Self {
+        self.resources = Arc::new(RwLock::new(resources));
+        self
+    }
+
+    /// A copy of the descriptor table, sharing the open resources
+    pub fn resources(&self) -> ResourceSet {
+        self.resources.read().clone()
+    }
+
+    /// A copy of the descriptor table for a spawned process
+    pub fn spawn_resources(&self) -> ResourceSet {
+        self.resources.read().spawn_copy()
+    }
+
+    pub fn set_cloexec(&self, fd: u8) {
+        self.resources.write().set_cloexec(fd)
+    }
+
+    pub fn open(&self, res: Resource) -> Option<u8> {
+        self.resources.write().open(res)
+    }
+
+    pub fn close(&self, fd: u8) -> bool {
+        self.resources.write().close(fd)
+    }
+
+    pub fn dup(&self, fd: u8) -> Option<u8> {
+        self.resources.write().dup(fd)
+    }
+
+    pub fn dup2(&self, old_fd: u8, new_fd: u8) -> Option<u8> {
+        self.resources.write().dup2(old_fd, new_fd)
+    }
+
+    pub fn resource(&self, fd: u8) -> Option<Arc<Mutex<Resource>>> {
+        self.resources.read().get(fd)
+    }
+
     pub fn read(&self, fd: u8, buf: &mut [u8]) -> isize {
         self.resources.read().read(fd, buf)
     }
@@ -62,4 +114,20 @@ impl ProcessData {
     pub fn write(&self, fd: u8, buf: &[u8]) -> isize {
         self.resources.read().write(fd, buf)
     }
+
+    pub fn seek(&self, fd: u8, pos: SeekFrom) -> isize {
+        self.resources.read().seek(fd, pos)
+    }
+
+    pub fn pread(&self, fd: u8, buf: &mut [u8], offset: usize) -> isize {
+        self.resources.read().pread(fd, buf, offset)
+    }
+
+    pub fn getdents(&self, fd: u8, buf: &mut [u8]) -> isize {
+        self.resources.read().getdents(fd, buf)
+    }
+
+    pub fn fstat(&self, fd: u8) -> Option<Stat> {
+        self.resources.read().fstat(fd)
+    }
 }
@@ -0,0 +1,46 @@
+use super::{manager::get_process_manager, ProcessId};
+use alloc::collections::*;
+use spin::Mutex;
+use x86_64::PhysAddr;
+
+/// Processes waiting on a futex, keyed by the physical address of the
+/// word so that processes sharing memory at any address meet
+static FUTEXES: Mutex<BTreeMap<PhysAddr, VecDeque<ProcessId>>> = Mutex::new(BTreeMap::new());
+
+pub fn wait(addr: PhysAddr, pid: ProcessId) {
+    FUTEXES.lock().entry(addr).or_default().push_back(pid);
+}
+
+/// Drop the reaped `pid` from every futex
+pub fn forget(pid: ProcessId) {
+    let mut futexes = FUTEXES.lock();
+    for waiters in futexes.values_mut() {
+        waiters.retain(|&waiter| waiter != pid);
+    }
+    futexes.retain(|_, waiters| !waiters.is_empty());
+}
+
+/// Wake up `count` processes waiting on `addr` at most, returning how many
+pub fn wake(addr: PhysAddr, count: usize) -> usize {
+    let mut futexes = FUTEXES.lock();
+    let Some(waiters) = futexes.get_mut(&addr) else {
+        return 0;
+    };
+
+    let manager = get_process_manager();
+    let mut woken = 0;
+    while woken < count {
+        let Some(pid) = waiters.pop_front() else {
+            break;
+        };
+        // the waiter may be killed in the meantime
+        if manager.wake_up(pid, Some(0)) {
+            woken += 1;
+        }
+    }
+
+    if waiters.is_empty() {
+        futexes.remove(&addr);
+    }
+    woken
+}
@@ -76,7 +76,7 @@ impl ProcessManager {
     }
 
     pub fn check_proc(&self, pid:&ProcessId) -> Option<isize>{
-        self.get_proc(pid).unwrap().read().exit_code()
+        self.get_proc(pid)?.read().exit_code()
     }
 
     pub fn save_current(&self, context: &ProcessContext) {
@@ -111,7 +111,10 @@ impl ProcessManager {
         let mut ready = self.ready_queue.lock();
         while !ready.is_empty(){
             let pid = ready.pop_front().unwrap();
-            let new = self.get_proc(&pid).unwrap();
+            // a process may be reaped while in the queue
+            let Some(new) = self.get_proc(&pid) else {
+                continue;
+            };
             let mut new_inner = new.write();
             if new_inner.is_ready() {
                 new_inner.resume();
@@ -156,10 +159,10 @@ impl ProcessManager {
         name: String,
         parent: Option<Weak<Process>>,
         proc_data: Option<ProcessData>,
-    ) -> ProcessId {
+    ) -> Option<ProcessId> {
         let kproc = self.get_proc(&KERNEL_PID).unwrap();
         let page_table = kproc.read().clone_page_table();
-        let proc = Process::new(name, parent, page_table, proc_data);
+        let proc = Process::new(name, parent, page_table, proc_data)?;
         let pid = proc.pid();
         debug!("spawning");
         {      
@@ -179,11 +182,15 @@ impl ProcessManager {
     
         trace!("New {:#?}", &proc);
     
+        if let Some(parent) = proc.read().parent() {
+            parent.write().add_child(proc.clone());
+        }
+
         // FIXME: something like kernel thread
         self.processes.write().insert(pid, proc);
         // FIXME: push to ready queue
         self.ready_queue.lock().push_back(pid);
-        pid
+        Some(pid)
     }
 
     pub fn kill_current(&self, ret: isize) {
@@ -230,14 +237,80 @@ impl ProcessManager {
             return;
         }
 
-        if let Some(pids) = self.wait_queue.lock().remove(&pid) {
-            for pid in pids {
-                self.wake_up(pid, Some(ret));
-            }
+        let waiters = self.wait_queue.lock().remove(&pid).unwrap_or_default();
+        // it waits for nothing anymore
+        self.unwait(pid);
+        // they run `wait_pid` again to take the exit code
+        for pid in waiters {
+            self.wake_up(pid, None);
         }
         trace!("Kill {:#?}", &proc);
 
         proc.kill(ret);
+        self.reparent_children(&proc);
+
+        // the kernel reaps its children at once, others are reaped by the
+        // parent, which is told by `Signal::Chld` unless it ignores that
+        let parent = proc.read().parent();
+        match parent {
+            Some(parent) if parent.pid() != KERNEL_PID => {
+                if parent.read().signals.reaps_children() {
+                    self.reap(pid);
+                } else {
+                    super::kill(parent.pid(), Signal::Chld);
+                }
+            }
+            _ => {
+                self.reap(pid);
+            }
+        }
+    }
+
+    /// Hand the children of a dead process over to the kernel,
+    /// reaping those already dead
+    fn reparent_children(&self, proc: &Arc<Process>) {
+        let kproc = self.get_proc(&KERNEL_PID).unwrap();
+        let children = proc.write().take_children();
+        for child in children {
+            child.write().set_parent(Arc::downgrade(&kproc));
+            let dead = child.read().status() == ProgramStatus::Dead;
+            if dead {
+                self.reap(child.pid());
+            } else {
+                kproc.write().add_child(child);
+            }
+        }
+    }
+
+    /// Remove a dead process for good, returning its exit code
+    pub fn reap(&self, pid: ProcessId) -> Option<isize> {
+        let proc = self.get_proc(&pid)?;
+        let ret = proc.read().exit_code()?;
+
+        self.processes.write().remove(&pid);
+        let parent = proc.read().parent();
+        if let Some(parent) = parent {
+            parent.write().remove_child(pid);
+        }
+        self.ready_queue.lock().retain(|&ready| ready != pid);
+        super::forget(pid);
+        pid.free();
+
+        trace!("Reaped process #{} with ret code: {}", pid, ret);
+        Some(ret)
+    }
+
+    /// The live processes in the group `pgid`
+    pub fn process_group(&self, pgid: ProcessId) -> Vec<ProcessId> {
+        self.processes
+            .read()
+            .iter()
+            .filter(|(_, p)| {
+                let inner = p.read();
+                inner.pgid() == pgid && inner.status() != ProgramStatus::Dead
+            })
+            .map(|(pid, _)| *pid)
+            .collect()
     }
 
     pub fn print_process_list(&self) {
@@ -262,16 +335,20 @@ impl ProcessManager {
         self.kill(get_process_manager().current().pid(), ret);
     }
     
-    pub fn fork(&self) {
+    /// Fork the current process, `false` if there is no pid left
+    pub fn fork(&self) -> bool {
         // FIXME: get current process
         let now = self.current();
         // FIXME: fork to get child
-        let child = now.fork();
+        let Some(child) = now.fork() else {
+            return false;
+        };
         // FIXME: add child to process list
         let c_pid=child.pid();
         self.add_proc(c_pid, child);
         self.push_ready(c_pid);
         // FOR DBG: maybe print the process ready queue?
+        true
     }
 
     pub fn block(&self, pid: ProcessId) {
@@ -288,13 +365,26 @@ impl ProcessManager {
         wait_queue.entry(pid).or_default().insert(self.current().pid());
     }
 
+    /// Stop `waiter` waiting for any process
+    pub fn unwait(&self, waiter: ProcessId) {
+        self.wait_queue.lock().retain(|_, pids| {
+            pids.remove(&waiter);
+            !pids.is_empty()
+        });
+    }
+
     /// Wake up the process with the given pid
     ///
-    /// If `ret` is `Some`, set the return value of the process
-    pub fn wake_up(&self, pid: ProcessId, ret: Option<isize>) {
+    /// If `ret` is `Some`, set the return value of the process,
+    /// returns whether the process was blocked
+    pub fn wake_up(&self, pid: ProcessId, ret: Option<isize>) -> bool {
         // info!("ads");
         if let Some(proc) = self.get_proc(&pid) {
             let mut inner = proc.write();
+            // a process may be woken up by several events at once
+            if inner.status() != ProgramStatus::Blocked {
+                return false;
+            }
             if let Some(ret) = ret {
                 // FIXME: set the return value of the process
                 //        like `context.set_rax(ret as usize)`
@@ -305,7 +395,9 @@ impl ProcessManager {
             // print!("asdasdas");
             inner.resume();
             inner.pause();
-            self.push_ready(pid)
+            self.push_ready(pid);
+            return true;
         }
+        false
     }
 }
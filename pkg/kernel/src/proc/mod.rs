@@ -1,29 +1,40 @@
 mod context;
 mod data;
+mod futex;
 pub mod manager;
 mod paging;
 mod pid;
 mod process;
+mod msg;
 mod processor;
+mod shm;
+mod signal;
 mod sync;
+mod timer;
 
 use alloc::string::ToString;
 use manager::*;
 use process::*;
-use storage::FileSystem;
+use storage::{FileSystem, SeekFrom};
+use syscall_def::{OpenFlags, SemError, SigHow, Signal, Stat};
 use sync::*;
+use msg::MsgResult;
+use signal::{Delivery, SignalFrame};
 use crate::filesystem::get_rootfs;
+use crate::resource::Resource;
 use crate::memory::PAGE_SIZE;
 
 use xmas_elf::ElfFile;
 use alloc::{string::String, sync::Arc, vec::Vec};
-pub use context::ProcessContext;
+pub use context::{ProcessContext, ProcessContextValue};
 pub use paging::PageTableContext;
 pub use data::ProcessData;
 pub use pid::ProcessId;
+pub use signal::SigAction;
 
+use x86_64::registers::rflags::RFlags;
 use x86_64::structures::idt::PageFaultErrorCode;
-use x86_64::VirtAddr;
+use x86_64::{PrivilegeLevel, VirtAddr};
 
 // 0xffff_ff00_0000_0000 is the kernel's address space
 pub const STACK_MAX: u64 = 0x0000_4000_0000_0000;
@@ -67,6 +78,7 @@ pub fn init(boot_info: &'static boot::BootInfo) {
     let kproc = { 
         /* FIXME: create kernel process */ 
         Process::new(String::from("kernel"), None, PageTableContext::new(), Some(kproc_data))
+            .expect("No pid for the kernel")
     };
     let app_list = boot_info.loaded_apps.as_ref();
     manager::init(kproc, app_list);
@@ -79,8 +91,10 @@ pub fn switch(context: &mut ProcessContext) {
         // FIXME: switch to the next process
         // info!("in switch");
         let manager = get_process_manager();
+        timer::expire();
         manager.save_current(context);
         manager.switch_next(context);
+        handle_signals(context);
     });
 }
 
@@ -97,34 +111,58 @@ pub fn spawn(path: &str) -> Option<ProcessId> {
     let name = parts.last().unwrap();
 
     let fs = get_rootfs();
-    let f = fs.open_file(path);
-
-    let mut buf = Vec::new(); 
-    let elf = match f {
-        Ok(mut file) => {
-            let _ = file.read_all(&mut buf);
-            xmas_elf::ElfFile::new(&buf)
-        },
-        Err(_) => {
-            Err("Error opening file")
-        }, 
-    }.unwrap();
+    let mut file = match fs.open_file(path) {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("Failed to open {}: {:?}", path, e);
+            return None;
+        }
+    };
+
+    let mut buf = Vec::new();
+    if let Err(e) = file.read_all(&mut buf) {
+        warn!("Failed to read {}: {:?}", path, e);
+        return None;
+    }
+
+    let elf = match xmas_elf::ElfFile::new(&buf) {
+        Ok(elf) => elf,
+        Err(e) => {
+            warn!("{} is not a valid ELF file: {}", path, e);
+            return None;
+        }
+    };
 
     elf_spawn(name.to_string(), &elf)
 }
 
 pub fn elf_spawn(name: String, elf: &ElfFile) -> Option<ProcessId> {
-    let pid = x86_64::instructions::interrupts::without_interrupts(|| {
+    x86_64::instructions::interrupts::without_interrupts(|| {
         let manager = get_process_manager();
         let process_name = name.to_lowercase();
-        let parent = Arc::downgrade(&manager.current());
-        let pid = manager.spawn(elf, name, Some(parent), None);
+        let current = manager.current();
+        let parent = Arc::downgrade(&current);
+        // the child inherits the descriptors, e.g. a redirected stdout
+        let proc_data = ProcessData::new().with_resources(current.read().spawn_resources());
+        let Some(pid) = manager.spawn(elf, name, Some(parent), Some(proc_data)) else {
+            warn!("No pid left to spawn {}", process_name);
+            return None;
+        };
+        // processes started by the kernel lead their own groups and sessions
+        if current.pid() != KERNEL_PID {
+            let (pgid, sid) = {
+                let inner = current.read();
+                (inner.pgid(), inner.sid())
+            };
+            let child = manager.get_proc(&pid).unwrap();
+            let mut inner = child.write();
+            inner.set_pgid(pgid);
+            inner.set_sid(sid);
+        }
 
         debug!("Spawned process: {}#{}", process_name, pid);
-        pid
-    });
-
-    Some(pid)
+        Some(pid)
+    })
 }
 
 pub fn print_process_list() {
@@ -171,12 +209,140 @@ pub fn list_app() {
     println!("[+] App list: {}", apps);
 }
 
-pub fn read(fd: u8, mut buf: &mut [u8]) -> isize {
-    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().write().read(fd, &mut buf))
+pub fn open(res: Resource) -> Option<u8> {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().open(res))
+}
+
+pub fn dup(fd: u8) -> Option<u8> {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().dup(fd))
+}
+
+pub fn dup2(old_fd: u8, new_fd: u8) -> Option<u8> {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().dup2(old_fd, new_fd))
+}
+
+pub fn close(fd: u8) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().close(fd))
+}
+
+/// Read from `fd`, blocking the process while a pipe is empty
+pub fn read(fd: u8, buf: &mut [u8], context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let current = manager.current();
+
+        // the lock of the process must be released before blocking
+        let res = current.read().resource(fd);
+        let ret = match res {
+            Some(res) => {
+                let mut res = res.lock();
+                if res.wait_read(current.pid()) {
+                    drop(res);
+                    block_and_retry(context);
+                    return;
+                }
+                res.read(buf).map_or(-1, |len| len as isize)
+            }
+            None => -1,
+        };
+
+        context.set_rax(ret as usize);
+    })
+}
+
+/// Write to `fd`, blocking the process while a pipe is full
+pub fn write(fd: u8, buf: &[u8], context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let current = manager.current();
+
+        // a write blocked on a full pipe is retried with the whole
+        // buffer, and goes on after what earlier rounds wrote
+        let mut done = current.write().resume_write(fd, buf);
+
+        // the lock of the process must be released before blocking
+        let res = current.read().resource(fd);
+        let Some(res) = res else {
+            context.set_rax(-1isize as usize);
+            return;
+        };
+
+        let mut rest = &buf[done..];
+        let mut res = res.lock();
+        let ret = loop {
+            if res.wait_write(current.pid()) {
+                drop(res);
+                current.write().block_write(fd, buf, done);
+                block_and_retry(context);
+                return;
+            }
+            match res.write(rest) {
+                Some(len) => {
+                    done += len;
+                    rest = &rest[len..];
+                    if rest.is_empty() || len == 0 {
+                        break done as isize;
+                    }
+                }
+                // no reader is left, report what made it through
+                None if done > 0 => break done as isize,
+                None => break -1,
+            }
+        };
+
+        context.set_rax(ret as usize);
+    })
+}
+
+/// Block the current process, it runs the same syscall again once woken up
+fn block_and_retry(context: &mut ProcessContext) {
+    let manager = get_process_manager();
+    context.retry_syscall();
+    manager.save_current(context);
+    manager.current().write().block();
+    manager.switch_next(context);
 }
 
-pub fn write(fd: u8, buf: &[u8]) -> isize {
-    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().write().write(fd, &buf))
+/// Create a pipe, returning the fds of its read and write ends
+///
+/// With `OpenFlags::CLOEXEC` spawned processes do not inherit them.
+pub fn pipe(flags: OpenFlags) -> Option<(u8, u8)> {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let current = get_process_manager().current();
+        let inner = current.read();
+        let (read, write) = crate::pipe::pipe();
+
+        let read = inner.open(Resource::Pipe(read))?;
+        match inner.open(Resource::Pipe(write)) {
+            Some(write) => {
+                if flags.contains(OpenFlags::CLOEXEC) {
+                    inner.set_cloexec(read);
+                    inner.set_cloexec(write);
+                }
+                Some((read, write))
+            }
+            None => {
+                inner.close(read);
+                None
+            }
+        }
+    })
+}
+
+pub fn seek(fd: u8, pos: SeekFrom) -> isize {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().seek(fd, pos))
+}
+
+pub fn pread(fd: u8, buf: &mut [u8], offset: usize) -> isize {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().pread(fd, buf, offset))
+}
+
+pub fn getdents(fd: u8, buf: &mut [u8]) -> isize {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().getdents(fd, buf))
+}
+
+pub fn fstat(fd: u8) -> Option<Stat> {
+    x86_64::instructions::interrupts::without_interrupts(|| get_process_manager().current().read().fstat(fd))
 }
 
 pub fn exit(ret: isize, context: &mut ProcessContext) {
@@ -193,7 +359,9 @@ pub fn still_alive(pid: ProcessId) -> bool {
     x86_64::instructions::interrupts::without_interrupts(|| {
         // check if the process is still alive
         // print_process_list();
-        get_process_manager().get_proc(&pid).unwrap().read().status() != ProgramStatus::Dead
+        get_process_manager()
+            .get_proc(&pid)
+            .is_some_and(|proc| proc.read().status() != ProgramStatus::Dead)
     })
 }
 
@@ -203,60 +371,152 @@ pub fn fork(context: &mut ProcessContext) {
         // FIXME: save_current as parent
         manager.save_current(context);
         // FIXME: fork to get child
-        manager.fork();
+        if !manager.fork() {
+            context.set_rax(-1isize as usize);
+            return;
+        }
         // FIXME: push to child & parent to ready queue
         // FIXME: switch to next process
         manager.switch_next(context);
     })
 }
 
-pub fn wait_pid(pid: ProcessId, context: &mut ProcessContext) {
+/// Drop the reaped `pid` from every queue it may wait in, so what
+/// is meant for it does not reach the next process to get the pid
+fn forget(pid: ProcessId) {
+    crate::pipe::forget(pid);
+    crate::input::forget(pid);
+    futex::forget(pid);
+    SEMAPHORES.lock().forget(pid);
+    msg::MESSAGE_QUEUES.lock().forget(pid);
+    timer::cancel(pid);
+
+    // the console stops signaling a group once it is gone
+    if get_process_manager()
+        .process_group(ProcessId(crate::input::foreground()))
+        .is_empty()
+    {
+        crate::input::set_foreground(0);
+    }
+}
+
+/// Wait for the child `pid` to exit, or for any child if it is `None`,
+/// and reap it
+///
+/// Returns the pid of the child and writes its exit code to `status`,
+/// 0 if `nohang` is set and no child has exited, or -1 if there is no such child.
+pub fn wait_pid(
+    pid: Option<ProcessId>,
+    nohang: bool,
+    status: Option<&mut isize>,
+    context: &mut ProcessContext,
+) {
     x86_64::instructions::interrupts::without_interrupts(|| {
         let manager = get_process_manager();
-        if let Some(ret) = manager.check_proc(&pid) {
-            context.set_rax(ret as usize);
+        let current = manager.current();
+        // it may be woken up by another child last time
+        manager.unwait(current.pid());
+
+        let children: Vec<(ProcessId, bool)> = current
+            .read()
+            .children()
+            .iter()
+            .filter(|child| pid.is_none_or(|pid| pid == child.pid()))
+            .map(|child| (child.pid(), child.read().status() == ProgramStatus::Dead))
+            .collect();
+
+        if children.is_empty() {
+            context.set_rax(usize::MAX);
+            return;
+        }
+
+        if let Some(&(pid, _)) = children.iter().find(|(_, dead)| *dead) {
+            let ret = manager.reap(pid).unwrap();
+            if let Some(status) = status {
+                *status = ret;
+            }
+            context.set_rax(pid.0 as usize);
+        } else if nohang {
+            context.set_rax(0);
         } else {
-            manager.wait_pid(pid);
-            manager.save_current(context);
-            manager.current().write().block();
-            manager.switch_next(context);
+            for &(pid, _) in children.iter() {
+                manager.wait_pid(pid);
+            }
+            block_and_retry(context);
         }
     })
 }
 
-pub fn new_sem(key: u32, value: usize) -> usize{
+pub fn new_sem(key: u32, value: usize, shared: bool) -> usize{
     let manager = get_process_manager();
     let now = manager.current();
-    if now.write().sem_new(key, value) {
+    if now.write().sem_new(key, value, now.pid(), shared) {
         0
     }else {
         1
     }
 }
 
-pub fn remove_sem(key: u32) -> usize{
+pub fn open_sem(key: u32) -> usize {
     let manager = get_process_manager();
     let now = manager.current();
-    if now.write().sem_remove(key) {
+    if now.write().sem_open(key, now.pid()) {
         0
-    }else {
+    } else {
+        1
+    }
+}
+
+pub fn close_sem(key: u32) -> usize {
+    let manager = get_process_manager();
+    if manager.current().write().sem_close(key) {
+        0
+    } else {
         1
     }
 }
 
+/// Remove the semaphore from the system, the processes
+/// waiting on it are woken up with an error
+pub fn remove_sem(key: u32) -> usize{
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let pid = manager.current().pid();
+        let waiters = SEMAPHORES.lock().remove(key, pid);
+        match waiters {
+            Some(waiters) => {
+                for pid in waiters {
+                    timer::cancel(pid);
+                    manager.wake_up(pid, Some(1));
+                }
+                0
+            }
+            None => 1,
+        }
+    })
+}
+
 pub fn sem_signal(key: u32, context: &mut ProcessContext) {
     x86_64::instructions::interrupts::without_interrupts(|| {
         let manager = get_process_manager();
-        let ret = manager.current().write().sem_signal(key);
-        match ret {
-            SemaphoreResult::Ok => context.set_rax(0),
-            SemaphoreResult::NotExist => context.set_rax(1),
-            SemaphoreResult::WakeUp(pid) => {
-                // FIXME: 与 wait_pid 系统调用类似，你需要在 sem_signal 中对进程进行唤醒。
-                // 但是此处无需为进程设置返回值，因此在调用 wake_up 时，传入 None 即可。
-                manager.wake_up(pid, None);
+        loop {
+            let ret = manager.current().write().sem_signal(key);
+            match ret {
+                SemaphoreResult::Ok => context.set_rax(0),
+                SemaphoreResult::NotExist => context.set_rax(1),
+                SemaphoreResult::WakeUp(pid) => {
+                    // FIXME: 与 wait_pid 系统调用类似，你需要在 sem_signal 中对进程进行唤醒。
+                    // the waiter gets 0 as its `wait` succeeds, `remove` wakes it with 1
+                    timer::cancel(pid);
+                    // a waiter killed by a signal passes the turn on
+                    if !manager.wake_up(pid, Some(0)) {
+                        continue;
+                    }
+                    context.set_rax(0);
+                }
+                _ => unreachable!(),
             }
-            _ => unreachable!(),
+            break;
         }
     })
 }
@@ -281,4 +541,416 @@ pub fn sem_wait(key: u32, context: &mut ProcessContext) {
             _ => unreachable!(),
         }
     })
-}
\ No newline at end of file
+}
+
+/// Wait the semaphore only if it does not block
+pub fn sem_try_wait(key: u32) -> usize {
+    let manager = get_process_manager();
+    match manager.current().read().sem_try_wait(key) {
+        SemaphoreResult::Ok => 0,
+        SemaphoreResult::WouldBlock => SemError::WouldBlock as usize,
+        _ => SemError::NotExist as usize,
+    }
+}
+
+/// Wait the semaphore for `ms` milliseconds at most
+pub fn sem_timed_wait(key: u32, ms: u64, context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let current = manager.current();
+        let pid = current.pid();
+
+        let sem = current.read().sem(key);
+        let Some(sem) = sem else {
+            context.set_rax(SemError::NotExist as usize);
+            return;
+        };
+
+        let ret = sem.lock().wait(pid);
+        match ret {
+            SemaphoreResult::Ok => context.set_rax(0),
+            SemaphoreResult::Block(pid) => {
+                // woken up by `signal` with 0, or by the timer
+                let deadline = crate::clock::uptime_ms().saturating_add(ms);
+                timer::add(pid, deadline, Arc::downgrade(&sem));
+                manager.save_current(context);
+                current.write().block();
+                manager.switch_next(context);
+            }
+            _ => context.set_rax(SemError::NotExist as usize),
+        }
+    })
+}
+
+pub fn shm_create(key: u32, size: usize) -> bool {
+    shm::SHARED_MEMORY.lock().create(key, size)
+}
+
+pub fn shm_remove(key: u32) -> bool {
+    shm::SHARED_MEMORY.lock().remove(key)
+}
+
+pub fn shm_attach(key: u32) -> Option<VirtAddr> {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        get_process_manager().current().write().shm_attach(key)
+    })
+}
+
+pub fn shm_detach(key: u32) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        get_process_manager().current().write().shm_detach(key)
+    })
+}
+
+pub fn msg_get(key: u32, create: bool) -> bool {
+    msg::MESSAGE_QUEUES.lock().get(key, create)
+}
+
+pub fn msg_remove(key: u32) -> bool {
+    msg::MESSAGE_QUEUES.lock().remove(key)
+}
+
+/// Send a message, blocking the process while the queue is full
+pub fn msg_send(key: u32, ty: isize, text: &[u8], nowait: bool, context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let pid = get_process_manager().current().pid();
+        let waiter = (!nowait).then_some(pid);
+
+        let ret = msg::MESSAGE_QUEUES.lock().send(key, ty, text, waiter);
+        match ret {
+            MsgResult::Sent => context.set_rax(0),
+            MsgResult::Block if !nowait => block_and_retry(context),
+            _ => context.set_rax(usize::MAX),
+        }
+    })
+}
+
+/// Receive a message of `ty` into `buf`, blocking the process until
+/// there is one, returns the type of the message received
+pub fn msg_receive(
+    key: u32,
+    ty: isize,
+    buf: &mut [u8],
+    nowait: bool,
+    context: &mut ProcessContext,
+) -> Option<isize> {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let pid = get_process_manager().current().pid();
+        let waiter = (!nowait).then_some(pid);
+
+        let ret = msg::MESSAGE_QUEUES.lock().receive(key, ty, buf, waiter);
+        match ret {
+            MsgResult::Received { ty, len } => {
+                context.set_rax(len);
+                return Some(ty);
+            }
+            MsgResult::Block if !nowait => block_and_retry(context),
+            _ => context.set_rax(usize::MAX),
+        }
+        None
+    })
+}
+
+/// Block on the futex at `addr` if it still holds `expected`
+pub fn futex_wait(addr: VirtAddr, expected: u32, context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let current = manager.current();
+
+        let phys = current.read().translate_user(addr, core::mem::size_of::<u32>());
+        let Some(phys) = phys else {
+            context.set_rax(usize::MAX);
+            return;
+        };
+
+        // no one can change the word before the process is queued,
+        // as interrupts are off and the memory is mapped
+        let value = unsafe { addr.as_ptr::<u32>().read_volatile() };
+        if value != expected {
+            context.set_rax(usize::MAX);
+            return;
+        }
+
+        futex::wait(phys, current.pid());
+        manager.save_current(context);
+        current.write().block();
+        manager.switch_next(context);
+    })
+}
+
+/// Wake up `count` processes waiting on the futex at `addr` at most
+pub fn futex_wake(addr: VirtAddr, count: usize) -> usize {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let current = get_process_manager().current();
+        let phys = current.read().translate_user(addr, core::mem::size_of::<u32>());
+        match phys {
+            Some(phys) => futex::wake(phys, count),
+            None => usize::MAX,
+        }
+    })
+}
+
+/// Send `sig` to `pid`
+///
+/// A signal that terminates the process does so at once, even if it is
+/// blocked in a syscall, while handlers run when it next returns to user mode
+pub fn kill(pid: ProcessId, sig: Signal) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        if pid == KERNEL_PID {
+            return false;
+        }
+        let Some(proc) = manager.get_proc(&pid) else {
+            return false;
+        };
+
+        let terminate = {
+            let mut inner = proc.write();
+            if inner.status() == ProgramStatus::Dead {
+                return false;
+            }
+            inner.signals.send(sig);
+            inner.signals.terminates(sig)
+        };
+
+        // the current process is terminated by `handle_signals` instead
+        if terminate && pid != manager.current().pid() {
+            manager.kill(pid, 128 + sig as isize);
+        }
+        true
+    })
+}
+
+/// Send `sig` to `pid` for the current process, which must be
+/// its parent or in the same session
+pub fn send_signal(pid: ProcessId, sig: Signal) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let current = manager.current();
+        let Some(proc) = manager.get_proc(&pid) else {
+            return false;
+        };
+
+        let allowed = {
+            let inner = proc.read();
+            inner.sid() == current.read().sid()
+                || inner.parent().is_some_and(|parent| parent.pid() == current.pid())
+        };
+        allowed && kill(pid, sig)
+    })
+}
+
+/// Send `sig` to every process in the group `pgid`,
+/// returns whether there is any to send to
+pub fn kill_group(pgid: ProcessId, sig: Signal) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let pids = get_process_manager().process_group(pgid);
+        pids.iter().fold(false, |sent, &pid| kill(pid, sig) || sent)
+    })
+}
+
+/// Move `pid` into the group `pgid`, where 0 stands for the current
+/// process and for `pid` respectively
+///
+/// Only the current process and its children can be moved.
+pub fn set_pgid(pid: ProcessId, pgid: ProcessId) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let current = manager.current();
+        let pid = if pid.0 == 0 { current.pid() } else { pid };
+        let pgid = if pgid.0 == 0 { pid } else { pgid };
+
+        let Some(proc) = manager.get_proc(&pid) else {
+            return false;
+        };
+        let is_child = proc
+            .read()
+            .parent()
+            .is_some_and(|parent| parent.pid() == current.pid());
+        if pid != current.pid() && !is_child {
+            return false;
+        }
+
+        let mut inner = proc.write();
+        if inner.status() == ProgramStatus::Dead {
+            return false;
+        }
+        inner.set_pgid(pgid);
+        true
+    })
+}
+
+/// Let the console signal the group `pgid`, 0 for none
+///
+/// Like signals, the console only goes to groups in the session
+/// of the current process, and only from such a group.
+pub fn set_foreground(pgid: u16) -> bool {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        let sid = manager.current().read().sid();
+        // the sessions of the live members, none once the group is gone
+        let sessions = |pgid: u16| -> Vec<ProcessId> {
+            manager
+                .process_group(ProcessId(pgid))
+                .iter()
+                .filter_map(|pid| manager.get_proc(pid))
+                .map(|proc| proc.read().sid())
+                .collect()
+        };
+
+        let old = sessions(crate::input::foreground());
+        if !old.is_empty() && !old.contains(&sid) {
+            return false;
+        }
+        if pgid != 0 && !sessions(pgid).contains(&sid) {
+            return false;
+        }
+        crate::input::set_foreground(pgid);
+        true
+    })
+}
+
+pub fn sigaction(sig: Signal, action: SigAction) -> bool {
+    // a handler is entered and left by address, which must be in user space
+    if let SigAction::Handler { handler, restorer } = action {
+        if handler >= USER_SPACE_END || restorer >= USER_SPACE_END {
+            return false;
+        }
+    }
+
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        get_process_manager().current().write().signals.set_action(sig, action)
+    })
+}
+
+/// Change the blocked signals of the current process, returning the old mask
+pub fn sigprocmask(how: SigHow, set: u64) -> u64 {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        get_process_manager().current().write().signals.set_blocked(how, set)
+    })
+}
+
+/// Whether `len` bytes at `addr` lie in the stack region of `pid`
+fn on_user_stack(pid: ProcessId, addr: u64, len: u64) -> bool {
+    let stack_bot = STACK_MAX - pid.0 as u64 * STACK_MAX_SIZE;
+    addr > stack_bot - STACK_MAX_SIZE
+        && addr
+            .checked_add(len)
+            .is_some_and(|end| end <= stack_bot + STACK_DEF_SIZE)
+}
+
+/// Deliver the pending signals of the process in `context`
+/// before it returns to user mode
+pub fn handle_signals(context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let manager = get_process_manager();
+        // kernel threads take no signals
+        while context.stack_frame.code_segment.rpl() == PrivilegeLevel::Ring3 {
+            let current = manager.current();
+            let delivery = current.write().signals.take();
+            match delivery {
+                None => return,
+                Some(Delivery::Terminate(sig)) => {
+                    manager.kill(current.pid(), 128 + sig as isize);
+                    manager.switch_next(context);
+                }
+                Some(Delivery::Handle {
+                    sig,
+                    handler,
+                    restorer,
+                }) => {
+                    if enter_handler(&current, sig, handler, restorer, context) {
+                        return;
+                    }
+                    // no room for the frame, so the process cannot go on
+                    current.write().signals.send(Signal::Kill);
+                }
+            }
+        }
+    })
+}
+
+/// Rewrite `context` to run `handler` with a `SignalFrame` on the user stack
+///
+/// The handler returns to `restorer`, which calls `sigreturn` with the
+/// stack pointer at the frame.
+fn enter_handler(
+    proc: &Process,
+    sig: Signal,
+    handler: u64,
+    restorer: u64,
+    context: &mut ProcessContext,
+) -> bool {
+    let size = core::mem::size_of::<SignalFrame>() as u64;
+    let sp = context.stack_frame.stack_pointer.as_u64();
+    // skip the red zone of the interrupted function
+    let Some(frame_addr) = sp.checked_sub(128 + size).map(|addr| addr & !0xf) else {
+        return false;
+    };
+    let ret_addr = frame_addr - 8;
+    if !on_user_stack(proc.pid(), ret_addr, sp - ret_addr) {
+        return false;
+    }
+
+    let blocked = proc.write().signals.enter_handler(sig);
+    let frame = SignalFrame {
+        context: context.value,
+        blocked,
+    };
+
+    // the stack may grow by page faults, so no lock is held here
+    unsafe {
+        (frame_addr as *mut SignalFrame).write(frame);
+        (ret_addr as *mut u64).write(restorer);
+    }
+
+    context.value.stack_frame.stack_pointer = VirtAddr::new(ret_addr);
+    context.value.stack_frame.instruction_pointer = VirtAddr::new(handler);
+    context.value.regs.rdi = sig as usize;
+    true
+}
+
+/// The end of the lower canonical half, where user code and stacks live
+const USER_SPACE_END: u64 = 0x0000_8000_0000_0000;
+
+/// Return from a signal handler to the context saved in its frame
+pub fn sigreturn(context: &mut ProcessContext) {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let current = get_process_manager().current();
+        // `restorer` is entered by `ret`, so the frame is at the stack top
+        let frame_addr = context.stack_frame.stack_pointer.as_u64();
+        let size = core::mem::size_of::<SignalFrame>() as u64;
+        if frame_addr % 16 != 0 || !on_user_stack(current.pid(), frame_addr, size) {
+            current.write().signals.send(Signal::Kill);
+            return;
+        }
+
+        let frame = unsafe { (frame_addr as *const SignalFrame).read() };
+        let mut value = frame.context;
+
+        // `iretq` to a kernel or non-canonical address faults in the kernel
+        let rip = value.stack_frame.instruction_pointer.as_u64();
+        let rsp = value.stack_frame.stack_pointer.as_u64();
+        if rip >= USER_SPACE_END || rsp >= USER_SPACE_END {
+            current.write().signals.send(Signal::Kill);
+            return;
+        }
+
+        // the handler may have changed the frame, so only the
+        // arithmetic flags are taken and user mode is kept
+        let arith = RFlags::CARRY_FLAG
+            | RFlags::PARITY_FLAG
+            | RFlags::AUXILIARY_CARRY_FLAG
+            | RFlags::ZERO_FLAG
+            | RFlags::SIGN_FLAG
+            | RFlags::DIRECTION_FLAG
+            | RFlags::OVERFLOW_FLAG;
+        let flags = context.stack_frame.cpu_flags;
+        value.stack_frame.cpu_flags = (flags - arith) | (value.stack_frame.cpu_flags & arith);
+        value.stack_frame.code_segment = context.stack_frame.code_segment;
+        value.stack_frame.stack_segment = context.stack_frame.stack_segment;
+
+        context.value = value;
+        current.write().signals.restore_blocked(frame.blocked);
+    })
+}
@@ -0,0 +1,178 @@
+use super::{manager::get_process_manager, ProcessId};
+use alloc::{collections::*, vec::Vec};
+use spin::Mutex;
+
+/// Bytes of message text a queue holds before senders have to wait
+pub const MSG_QUEUE_CAPACITY: usize = 4096;
+
+pub static MESSAGE_QUEUES: Mutex<MsgQueueSet> = Mutex::new(MsgQueueSet::new());
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct MsgQueueId(u32);
+
+impl MsgQueueId {
+    pub fn new(key: u32) -> Self {
+        Self(key)
+    }
+}
+
+#[derive(Debug)]
+struct Message {
+    ty: isize,
+    text: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+pub struct MsgQueue {
+    messages: VecDeque<Message>,
+    bytes: usize,
+    send_waiters: VecDeque<ProcessId>,
+    recv_waiters: VecDeque<ProcessId>,
+}
+
+/// Message queue result
+#[derive(Debug)]
+pub enum MsgResult {
+    Sent,
+    Received { ty: isize, len: usize },
+    NotExist,
+    Invalid,
+    /// The caller is pushed into the wait queue, if it wants to wait
+    Block,
+}
+
+impl MsgQueue {
+    fn wake_all(waiters: &mut VecDeque<ProcessId>) {
+        let manager = get_process_manager();
+        for pid in waiters.drain(..) {
+            manager.wake_up(pid, None);
+        }
+    }
+
+    /// Find the message to receive for `ty`
+    ///
+    /// 0 takes the first message, a positive type takes the first message
+    /// of that type, and a negative one takes the first message of the
+    /// lowest type no greater than its absolute value
+    fn find(&self, ty: isize) -> Option<usize> {
+        match ty {
+            0 if self.messages.is_empty() => None,
+            0 => Some(0),
+            1.. => self.messages.iter().position(|msg| msg.ty == ty),
+            _ => self
+                .messages
+                .iter()
+                .enumerate()
+                .filter(|(_, msg)| msg.ty.unsigned_abs() <= ty.unsigned_abs())
+                .min_by_key(|(idx, msg)| (msg.ty, *idx))
+                .map(|(idx, _)| idx),
+        }
+    }
+
+    pub fn send(&mut self, ty: isize, text: &[u8], waiter: Option<ProcessId>) -> MsgResult {
+        if ty <= 0 || text.len() > MSG_QUEUE_CAPACITY {
+            return MsgResult::Invalid;
+        }
+
+        if self.bytes + text.len() > MSG_QUEUE_CAPACITY {
+            if let Some(pid) = waiter {
+                self.send_waiters.push_back(pid);
+            }
+            return MsgResult::Block;
+        }
+
+        self.bytes += text.len();
+        self.messages.push_back(Message {
+            ty,
+            text: text.to_vec(),
+        });
+
+        // receivers check the type of the new message themselves
+        Self::wake_all(&mut self.recv_waiters);
+        MsgResult::Sent
+    }
+
+    /// Take a message of `ty` into `buf`, the text is truncated to fit
+    pub fn receive(&mut self, ty: isize, buf: &mut [u8], waiter: Option<ProcessId>) -> MsgResult {
+        let Some(idx) = self.find(ty) else {
+            if let Some(pid) = waiter {
+                self.recv_waiters.push_back(pid);
+            }
+            return MsgResult::Block;
+        };
+
+        let msg = self.messages.remove(idx).unwrap();
+        let len = buf.len().min(msg.text.len());
+        buf[..len].copy_from_slice(&msg.text[..len]);
+        self.bytes -= msg.text.len();
+
+        Self::wake_all(&mut self.send_waiters);
+        MsgResult::Received { ty: msg.ty, len }
+    }
+}
+
+#[derive(Debug)]
+pub struct MsgQueueSet {
+    queues: BTreeMap<MsgQueueId, MsgQueue>,
+}
+
+impl MsgQueueSet {
+    pub const fn new() -> Self {
+        Self {
+            queues: BTreeMap::new(),
+        }
+    }
+
+    /// Make sure the queue of `key` exists, creating it if `create` is set
+    pub fn get(&mut self, key: u32, create: bool) -> bool {
+        trace!("Msg Get: <{:#x}>", key);
+
+        let qid = MsgQueueId::new(key);
+        if create {
+            self.queues.entry(qid).or_default();
+        }
+        self.queues.contains_key(&qid)
+    }
+
+    /// Remove the queue, waking up its waiters to fail
+    pub fn remove(&mut self, key: u32) -> bool {
+        trace!("Msg Remove: <{:#x}>", key);
+
+        match self.queues.remove(&MsgQueueId::new(key)) {
+            Some(mut queue) => {
+                MsgQueue::wake_all(&mut queue.send_waiters);
+                MsgQueue::wake_all(&mut queue.recv_waiters);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop the reaped `pid` from every wait queue
+    pub fn forget(&mut self, pid: ProcessId) {
+        for queue in self.queues.values_mut() {
+            queue.send_waiters.retain(|&waiter| waiter != pid);
+            queue.recv_waiters.retain(|&waiter| waiter != pid);
+        }
+    }
+
+    pub fn send(&mut self, key: u32, ty: isize, text: &[u8], waiter: Option<ProcessId>) -> MsgResult {
+        match self.queues.get_mut(&MsgQueueId::new(key)) {
+            Some(queue) => queue.send(ty, text, waiter),
+            None => MsgResult::NotExist,
+        }
+    }
+
+    pub fn receive(
+        &mut self,
+        key: u32,
+        ty: isize,
+        buf: &mut [u8],
+        waiter: Option<ProcessId>,
+    ) -> MsgResult {
+        match self.queues.get_mut(&MsgQueueId::new(key)) {
+            Some(queue) => queue.receive(ty, buf, waiter),
+            None => MsgResult::NotExist,
+        }
+    }
+}
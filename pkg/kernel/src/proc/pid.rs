@@ -1,19 +1,41 @@
-use core::sync::atomic::{AtomicU16, Ordering};
+use super::{shm::SHM_BASE, STACK_MAX, STACK_MAX_SIZE};
+use alloc::collections::VecDeque;
+use spin::Mutex;
+
+/// The stack of a pid may grow into the region of the next one,
+/// which has to stay above the shared memory
+pub(super) const MAX_PID: u16 = ((STACK_MAX - SHM_BASE) / STACK_MAX_SIZE) as u16 - 2;
+
+static PIDS: Mutex<PidAllocator> = Mutex::new(PidAllocator {
+    next: 1,
+    freed: VecDeque::new(),
+});
+
+/// Pids are taken in order, and those of reaped processes are reused
+/// oldest first once all are taken, so a stale pid hardly names a new process
+struct PidAllocator {
+    next: u16,
+    freed: VecDeque<u16>,
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProcessId(pub u16);
-static COUNTER: AtomicU16 = AtomicU16::new(1);
+
 impl ProcessId {
-    pub fn new() -> Self {
+    /// A pid not in use, `None` if all are taken
+    pub fn new() -> Option<Self> {
         // FIXME: Get a unique PID
-        let pid = COUNTER.fetch_add(1, Ordering::SeqCst);
-        Self(pid)
+        let mut pids = PIDS.lock();
+        if pids.next <= MAX_PID {
+            pids.next += 1;
+            return Some(Self(pids.next - 1));
+        }
+        pids.freed.pop_front().map(Self)
     }
-}
 
-impl Default for ProcessId {
-    fn default() -> Self {
-        Self::new()
+    /// Give the pid back after its process is reaped
+    pub fn free(self) {
+        PIDS.lock().freed.push_back(self.0);
     }
 }
 
@@ -5,8 +5,11 @@ use alloc::vec::Vec;
 use spin::*;
 use elf::{map_range, unmap_range};
 use alloc::sync::Arc;
-use x86_64::structures::paging::{PageSize, PageTableFlags, Size4KiB};
+use x86_64::structures::paging::{mapper::Translate, PageSize, PageTableFlags, Size4KiB};
+use x86_64::PhysAddr;
 use sync::*;
+use shm::SHARED_MEMORY;
+use signal::SignalState;
 
 #[derive(Clone)]
 pub struct Process {
@@ -20,11 +23,26 @@ pub struct ProcessInner {
     children: Vec<Arc<Process>>,
     ticks_passed: usize,
     status: ProgramStatus,
+    /// The process group, signaled as one by the console
+    pgid: ProcessId,
+    /// The session, whose processes may signal each other
+    sid: ProcessId,
     exit_code: Option<isize>,
     context: ProcessContext,
     pub(super) page_table: Option<PageTableContext>,
+    pub(super) signals: SignalState,
+    /// A write blocked on a full pipe, resumed by its retry
+    pending_write: Option<PendingWrite>,
     proc_data: Option<ProcessData>,
-    pub(super) semaphores: Arc<RwLock<SemaphoreSet>>,
+}
+
+/// How much of `len` bytes at `buf` a write to `fd` got through
+#[derive(Clone, Copy, Debug)]
+struct PendingWrite {
+    fd: u8,
+    buf: usize,
+    len: usize,
+    written: usize,
 }
 
 impl Process {
@@ -48,32 +66,35 @@ impl Process {
         parent: Option<Weak<Process>>,
         page_table: PageTableContext,
         proc_data: Option<ProcessData>,
-    ) -> Arc<Self> {
+    ) -> Option<Arc<Self>> {
         let name = name.to_ascii_lowercase();
 
         // create context
-        let pid = ProcessId::new();
+        let pid = ProcessId::new()?;
 
         let inner = ProcessInner {
             name,
             parent,
             status: ProgramStatus::Ready,
+            pgid: pid,
+            sid: pid,
             context: ProcessContext::default(),
             ticks_passed: 0,
             exit_code: None,
             children: Vec::new(),
             page_table: Some(page_table),
+            signals: SignalState::default(),
+            pending_write: None,
             proc_data: Some(proc_data.unwrap_or_default()),
-            semaphores: Arc::new(SemaphoreSet::default().into())
         };
 
         trace!("New process {}#{} created.", &inner.name, pid);
 
         // create process struct
-        Arc::new(Self {
+        Some(Arc::new(Self {
             pid,
             inner: Arc::new(RwLock::new(inner)),
-        })
+        }))
     }
 
     pub fn kill(&self, ret: isize) {
@@ -86,7 +107,13 @@ impl Process {
             ret
         );
 
-        inner.kill(ret);
+        let proc_data = inner.kill(ret);
+        drop(inner);
+        timer::cancel(self.pid);
+
+        // closing a pipe may wake up other processes,
+        // so the resources are dropped without holding the lock
+        drop(proc_data);
     }
 
     pub fn alloc_init_stack(&self, user_access: bool) -> VirtAddr {
@@ -127,14 +154,14 @@ impl Process {
         self.write().proc_data.as_mut().unwrap().set_stack(start, size);
     }
 
-    pub fn fork(self: &Arc<Self>) -> Arc<Self> {
+    /// Fork the process, `None` if there is no pid left
+    pub fn fork(self: &Arc<Self>) -> Option<Arc<Self>> {
         // FIXME: lock inner as write
         // FIXME: inner fork with parent weak ref
+        let pid = ProcessId::new()?;
         let mut now_inner = self.write();
-        let pid = ProcessId::new();
         let idx = now_inner.children.len();
-        let sem = Arc::clone(&now_inner.semaphores);
-        let new_inner = now_inner.fork(Arc::downgrade(self), &pid, idx, sem);
+        let new_inner = now_inner.fork(Arc::downgrade(self), &pid, idx);
         // FOR DBG: maybe print the child process info
         //          e.g. parent, name, pid, etc.
         // FIXME: make the arc of child
@@ -148,7 +175,7 @@ impl Process {
         // FIXME: set fork ret value for parent with `context.set_rax`
         now_inner.context.set_rax(pid.0 as usize);
         // FIXME: mark the child as ready & return it
-        child
+        Some(child)
     }
 }
 
@@ -165,6 +192,44 @@ impl ProcessInner {
         self.status
     }
 
+    pub fn pgid(&self) -> ProcessId {
+        self.pgid
+    }
+
+    pub fn set_pgid(&mut self, pgid: ProcessId) {
+        self.pgid = pgid;
+    }
+
+    pub fn sid(&self) -> ProcessId {
+        self.sid
+    }
+
+    pub fn set_sid(&mut self, sid: ProcessId) {
+        self.sid = sid;
+    }
+
+    /// Remember that a write of `buf` to `fd` blocked after `written` bytes
+    pub fn block_write(&mut self, fd: u8, buf: &[u8], written: usize) {
+        self.pending_write = Some(PendingWrite {
+            fd,
+            buf: buf.as_ptr() as usize,
+            len: buf.len(),
+            written,
+        });
+    }
+
+    /// Take how much of `buf` a blocked write to `fd` got through,
+    /// 0 if this is not the retry of one, e.g. a write in a signal handler
+    pub fn resume_write(&mut self, fd: u8, buf: &[u8]) -> usize {
+        match self.pending_write {
+            Some(w) if w.fd == fd && w.buf == buf.as_ptr() as usize && w.len == buf.len() => {
+                self.pending_write = None;
+                w.written
+            }
+            _ => 0,
+        }
+    }
+
     pub fn pause(&mut self) {
         self.status = ProgramStatus::Ready;
     }
@@ -205,10 +270,41 @@ impl ProcessInner {
         self.page_table.as_ref().unwrap().load()
     }
 
+    /// Translate `len` bytes at `addr` in user space, which must be mapped,
+    /// aligned and on one page
+    pub fn translate_user(&self, addr: VirtAddr, len: usize) -> Option<PhysAddr> {
+        let offset = addr.as_u64() % PAGE_SIZE;
+        if addr.as_u64() >= STACK_MAX || !addr.is_aligned(len as u64) || offset + len as u64 > PAGE_SIZE {
+            return None;
+        }
+
+        self.page_table.as_ref()?.mapper().translate_addr(addr)
+    }
+
     pub fn parent(&self) -> Option<Arc<Process>> {
         self.parent.as_ref().and_then(|p| p.upgrade())
     }
 
+    pub fn set_parent(&mut self, parent: Weak<Process>) {
+        self.parent = Some(parent);
+    }
+
+    pub fn children(&self) -> &[Arc<Process>] {
+        &self.children
+    }
+
+    pub fn add_child(&mut self, child: Arc<Process>) {
+        self.children.push(child);
+    }
+
+    pub fn remove_child(&mut self, pid: ProcessId) {
+        self.children.retain(|child| child.pid() != pid);
+    }
+
+    pub fn take_children(&mut self) -> Vec<Arc<Process>> {
+        core::mem::take(&mut self.children)
+    }
+
     pub fn free(&mut self){
         let frame_deallocator = &mut *get_frame_alloc_for_sure();
         let mut page_table = self.page_table.as_ref().unwrap().mapper();
@@ -219,27 +315,34 @@ impl ProcessInner {
         let _ = unmap_range(start_address, count, &mut page_table, frame_deallocator);
     }
 
-    pub fn kill(&mut self, ret: isize) {
+    /// Mark the process as dead, returning its data for the caller to drop
+    pub fn kill(&mut self, ret: isize) -> Option<ProcessData> {
         // FIXME: set exit code
         self.exit_code = Some(ret);
         // FIXME: set status to dead
         self.status = ProgramStatus::Dead;
         // FIXME: take and drop unused resources
+        self.shm_detach_all();
         self.free();
-        drop(self.proc_data.take());
+        self.proc_data.take()
     }
 
     pub fn init_stack(&mut self, entry:VirtAddr, top:VirtAddr){
         self.context.init_stack_frame(entry, top);
     }
 
-    pub fn fork(&mut self, parent: Weak<Process>, pid: &ProcessId, idx: usize, sem: Arc<RwLock<SemaphoreSet>>) -> ProcessInner {
+    pub fn fork(&mut self, parent: Weak<Process>, pid: &ProcessId, idx: usize) -> ProcessInner {
         // FIXME: get current process's stack info
         let stack_info = self.proc_data.as_ref().unwrap().stack_segment.unwrap();
         let start_addr = stack_info.start.start_address().as_u64();
         let stack_size = (stack_info.end.start_address().as_u64() - start_addr) / Size4KiB::SIZE;
         // FIXME: clone the process data struct
-        let mut cloned_proc_data = self.proc_data.clone().unwrap();
+        // the child gets its own copy of the descriptor table
+        let proc_data = self.proc_data.as_ref().unwrap();
+        let mut cloned_proc_data = proc_data.clone().with_resources(proc_data.resources());
+        // attachments are not inherited, though the shared page table
+        // keeps the parent's segments visible while it has them
+        cloned_proc_data.shm.clear();
     
         // FIXME: clone the page table context (see instructions)
         let cloned_page_table = self.page_table.as_ref().unwrap().fork();
@@ -273,11 +376,14 @@ impl ProcessInner {
             children: Vec::new(), 
             ticks_passed: 0, 
             status: ProgramStatus::Ready, 
+            pgid: self.pgid,
+            sid: self.sid,
             exit_code: None, 
             context: new_context, 
             page_table: Some(cloned_page_table), 
+            signals: self.signals.fork(),
+            pending_write: None,
             proc_data: Some(cloned_proc_data),
-            semaphores: sem
            }
     }
 
@@ -286,24 +392,75 @@ impl ProcessInner {
         self.context.set_rax(value)
     }
 
-    #[inline]
-    pub fn sem_new(&self, key: u32, value: usize) -> bool {
-        self.semaphores.write().insert(key, value)
+    pub fn sem_new(&mut self, key: u32, value: usize, pid: ProcessId, shared: bool) -> bool {
+        match SEMAPHORES.lock().insert(key, value, pid, shared) {
+            Some(sem) => {
+                self.semaphores.insert(key, sem);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn sem_open(&mut self, key: u32, pid: ProcessId) -> bool {
+        match SEMAPHORES.lock().open(key, pid) {
+            Some(sem) => {
+                self.semaphores.insert(key, sem);
+                true
+            }
+            None => false,
+        }
     }
 
     #[inline]
-    pub fn sem_remove(&self, key: u32) -> bool {
-        self.semaphores.write().remove(key)
+    pub fn sem_close(&mut self, key: u32) -> bool {
+        self.semaphores.close(key)
     }
 
     #[inline]
     pub fn sem_signal(&self, key: u32) -> SemaphoreResult {
-        self.semaphores.write().signal(key)
+        self.semaphores.signal(key)
     }
 
     #[inline]
     pub fn sem_wait(&self, key: u32, pid: ProcessId) -> SemaphoreResult {
-        self.semaphores.write().wait(key, pid)
+        self.semaphores.wait(key, pid)
+    }
+
+    #[inline]
+    pub fn sem_try_wait(&self, key: u32) -> SemaphoreResult {
+        self.semaphores.try_wait(key)
+    }
+
+    #[inline]
+    pub fn sem(&self, key: u32) -> Option<Arc<Mutex<Semaphore>>> {
+        self.semaphores.get(key)
+    }
+
+    pub fn shm_attach(&mut self, key: u32) -> Option<VirtAddr> {
+        let addr = SHARED_MEMORY
+            .lock()
+            .attach(key, self.page_table.as_ref().unwrap())?;
+        self.shm.push(key);
+        Some(addr)
+    }
+
+    pub fn shm_detach(&mut self, key: u32) -> bool {
+        let Some(idx) = self.shm.iter().position(|&k| k == key) else {
+            return false;
+        };
+        self.shm.swap_remove(idx);
+        SHARED_MEMORY
+            .lock()
+            .detach(key, self.page_table.as_ref().unwrap())
+    }
+
+    fn shm_detach_all(&mut self) {
+        let page_table = self.page_table.as_ref().unwrap();
+        let mut shm = SHARED_MEMORY.lock();
+        for key in self.proc_data.as_mut().unwrap().shm.drain(..) {
+            shm.detach(key, page_table);
+        }
     }
 }
 
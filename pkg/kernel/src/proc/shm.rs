@@ -0,0 +1,219 @@
+use super::{pid::MAX_PID, PageTableContext, STACK_MAX, STACK_MAX_SIZE};
+use crate::memory::*;
+use alloc::{collections::BTreeMap, vec::Vec};
+use spin::Mutex;
+use x86_64::{
+    structures::paging::{FrameAllocator, FrameDeallocator, PageTableFlags, PhysFrame},
+    VirtAddr,
+};
+
+// [0x3000_0000_0000..] is left for shared memory,
+// every segment has its own address in all processes
+pub const SHM_BASE: u64 = 0x0000_3000_0000_0000;
+/// The stacks of the largest pids start right above
+const SHM_END: u64 = STACK_MAX - (MAX_PID as u64 + 1) * STACK_MAX_SIZE;
+pub const SHM_MAX_PAGES: u64 = 0x1000;
+
+pub static SHARED_MEMORY: Mutex<ShmSet> = Mutex::new(ShmSet::new());
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ShmId(u32);
+
+impl ShmId {
+    pub fn new(key: u32) -> Self {
+        Self(key)
+    }
+}
+
+/// Frames mapped into every process that attaches them
+#[derive(Debug)]
+pub struct SharedMemory {
+    frames: Vec<PhysFrame>,
+    addr: u64,
+    /// Attach count by the level 4 frame of the page table,
+    /// as forked processes share one page table
+    attached: BTreeMap<PhysFrame, usize>,
+    removed: bool,
+}
+
+impl SharedMemory {
+    /// Allocate `pages` zeroed frames
+    fn new(pages: u64, addr: u64) -> Option<Self> {
+        let frame_alloc = &mut *get_frame_alloc_for_sure();
+        let mut frames = Vec::with_capacity(pages as usize);
+
+        for _ in 0..pages {
+            match frame_alloc.allocate_frame() {
+                Some(frame) => frames.push(frame),
+                None => {
+                    for frame in frames {
+                        unsafe { frame_alloc.deallocate_frame(frame) };
+                    }
+                    return None;
+                }
+            }
+        }
+
+        for frame in frames.iter() {
+            let ptr = physical_to_virtual(frame.start_address().as_u64()) as *mut u8;
+            unsafe { core::ptr::write_bytes(ptr, 0, PAGE_SIZE as usize) };
+        }
+
+        Some(Self {
+            frames,
+            addr,
+            attached: BTreeMap::new(),
+            removed: false,
+        })
+    }
+
+    fn free(self) {
+        let frame_alloc = &mut *get_frame_alloc_for_sure();
+        for frame in self.frames {
+            unsafe { frame_alloc.deallocate_frame(frame) };
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShmSet {
+    segments: BTreeMap<ShmId, SharedMemory>,
+}
+
+impl ShmSet {
+    pub const fn new() -> Self {
+        Self {
+            segments: BTreeMap::new(),
+        }
+    }
+
+    /// The lowest free address for `pages`, reusing the ranges of freed
+    /// segments and leaving an unmapped page after every segment
+    fn find_addr(&self, pages: u64) -> Option<u64> {
+        let mut used: Vec<_> = self
+            .segments
+            .values()
+            .map(|shm| (shm.addr, shm.frames.len() as u64))
+            .collect();
+        used.sort_unstable();
+
+        let mut addr = SHM_BASE;
+        for (start, len) in used {
+            if addr + (pages + 1) * PAGE_SIZE <= start {
+                break;
+            }
+            addr = start + (len + 1) * PAGE_SIZE;
+        }
+
+        (addr + pages * PAGE_SIZE <= SHM_END).then_some(addr)
+    }
+
+    pub fn create(&mut self, key: u32, size: usize) -> bool {
+        trace!("Shm Create: <{:#x}>{}", key, size);
+
+        let sid = ShmId::new(key);
+        let pages = (size as u64).div_ceil(PAGE_SIZE);
+        if pages == 0 || pages > SHM_MAX_PAGES || self.segments.contains_key(&sid) {
+            return false;
+        }
+
+        let Some(addr) = self.find_addr(pages) else {
+            warn!("No address left for shared memory <{:#x}>", key);
+            return false;
+        };
+
+        match SharedMemory::new(pages, addr) {
+            Some(shm) => {
+                self.segments.insert(sid, shm);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the segment, its frames are freed once no one attaches it
+    pub fn remove(&mut self, key: u32) -> bool {
+        trace!("Shm Remove: <{:#x}>", key);
+
+        let sid = ShmId::new(key);
+        match self.segments.get_mut(&sid) {
+            Some(shm) if !shm.removed => shm.removed = true,
+            _ => return false,
+        }
+
+        self.free_unused(sid);
+        true
+    }
+
+    /// Map the segment into `page_table`, returning its address
+    pub fn attach(&mut self, key: u32, page_table: &PageTableContext) -> Option<VirtAddr> {
+        trace!("Shm Attach: <{:#x}>", key);
+
+        let shm = self.segments.get_mut(&ShmId::new(key))?;
+        if shm.removed {
+            return None;
+        }
+
+        let count = shm.attached.entry(page_table.reg.addr).or_insert(0);
+        if *count == 0 {
+            let flags = PageTableFlags::USER_ACCESSIBLE
+                | PageTableFlags::WRITABLE
+                | PageTableFlags::NO_EXECUTE;
+
+            // map page by page, so a failure only unmaps what was mapped here
+            for (mapped, frame) in shm.frames.iter().enumerate() {
+                let ret = elf::map_frames(
+                    shm.addr + mapped as u64 * PAGE_SIZE,
+                    core::slice::from_ref(frame),
+                    &mut page_table.mapper(),
+                    &mut *get_frame_alloc_for_sure(),
+                    flags,
+                );
+
+                if let Err(e) = ret {
+                    warn!("Failed to attach shared memory <{:#x}>: {:?}", key, e);
+                    let _ = elf::unmap_pages(shm.addr, mapped as u64, &mut page_table.mapper());
+                    shm.attached.remove(&page_table.reg.addr);
+                    return None;
+                }
+            }
+        }
+
+        *count += 1;
+        Some(VirtAddr::new(shm.addr))
+    }
+
+    /// Undo an `attach`, unmapping the segment after the last one
+    pub fn detach(&mut self, key: u32, page_table: &PageTableContext) -> bool {
+        trace!("Shm Detach: <{:#x}>", key);
+
+        let sid = ShmId::new(key);
+        let Some(shm) = self.segments.get_mut(&sid) else {
+            return false;
+        };
+        let Some(count) = shm.attached.get_mut(&page_table.reg.addr) else {
+            return false;
+        };
+
+        *count -= 1;
+        if *count == 0 {
+            shm.attached.remove(&page_table.reg.addr);
+            let _ = elf::unmap_pages(shm.addr, shm.frames.len() as u64, &mut page_table.mapper());
+        }
+
+        self.free_unused(sid);
+        true
+    }
+
+    fn free_unused(&mut self, sid: ShmId) {
+        if self
+            .segments
+            .get(&sid)
+            .is_some_and(|shm| shm.removed && shm.attached.is_empty())
+        {
+            if let Some(shm) = self.segments.remove(&sid) {
+                shm.free();
+            }
+        }
+    }
+}
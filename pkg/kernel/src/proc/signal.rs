@@ -0,0 +1,165 @@
+use super::ProcessContextValue;
+use syscall_def::{SigHow, Signal, SIG_DFL, SIG_IGN};
+
+const MAX_SIGNAL: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SigAction {
+    #[default]
+    Default,
+    Ignore,
+    /// Run `handler` in user mode, which returns to `restorer`
+    /// to call `Syscall::SigReturn`
+    Handler { handler: u64, restorer: u64 },
+}
+
+impl SigAction {
+    pub fn new(handler: usize, restorer: usize) -> Self {
+        match handler {
+            SIG_DFL => Self::Default,
+            SIG_IGN => Self::Ignore,
+            _ => Self::Handler {
+                handler: handler as u64,
+                restorer: restorer as u64,
+            },
+        }
+    }
+}
+
+/// What to do with a signal taken from the pending ones
+#[derive(Debug)]
+pub enum Delivery {
+    Terminate(Signal),
+    Handle {
+        sig: Signal,
+        handler: u64,
+        restorer: u64,
+    },
+}
+
+/// What a handler needs to return to the interrupted code,
+/// pushed to the user stack below the red zone
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SignalFrame {
+    pub context: ProcessContextValue,
+    pub blocked: u64,
+}
+
+/// Pending and blocked signals of a process, and how it handles them
+#[derive(Clone, Debug, Default)]
+pub struct SignalState {
+    pending: u64,
+    blocked: u64,
+    actions: [SigAction; MAX_SIGNAL],
+}
+
+impl SignalState {
+    /// The state of a forked child, which has no pending signals
+    pub fn fork(&self) -> Self {
+        Self {
+            pending: 0,
+            ..self.clone()
+        }
+    }
+
+    fn action(&self, sig: Signal) -> SigAction {
+        if sig.catchable() {
+            self.actions[sig as usize]
+        } else {
+            SigAction::Default
+        }
+    }
+
+    fn ignores(&self, sig: Signal) -> bool {
+        match self.action(sig) {
+            SigAction::Default => sig.ignored_by_default(),
+            SigAction::Ignore => true,
+            SigAction::Handler { .. } => false,
+        }
+    }
+
+    /// Whether the children are reaped as they exit, as the process
+    /// ignores `Signal::Chld` on purpose instead of by default
+    pub fn reaps_children(&self) -> bool {
+        self.actions[Signal::Chld as usize] == SigAction::Ignore
+    }
+
+    /// Make `sig` pending, unless it is ignored
+    pub fn send(&mut self, sig: Signal) {
+        if !self.ignores(sig) {
+            self.pending |= sig.mask();
+        }
+    }
+
+    /// Whether the pending `sig` terminates the process right away
+    pub fn terminates(&self, sig: Signal) -> bool {
+        self.pending & !self.blocked & sig.mask() != 0
+            && self.action(sig) == SigAction::Default
+            && !sig.ignored_by_default()
+    }
+
+    pub fn set_action(&mut self, sig: Signal, action: SigAction) -> bool {
+        if !sig.catchable() {
+            return false;
+        }
+
+        self.actions[sig as usize] = action;
+        if self.ignores(sig) {
+            self.pending &= !sig.mask();
+        }
+        true
+    }
+
+    /// Change the blocked signals, returning the old mask
+    pub fn set_blocked(&mut self, how: SigHow, set: u64) -> u64 {
+        let old = self.blocked;
+        self.blocked = match how {
+            SigHow::Block => old | set,
+            SigHow::Unblock => old & !set,
+            SigHow::SetMask => set,
+        } & !Signal::Kill.mask();
+        old
+    }
+
+    /// Take the next signal to act on, dropping those ignored meanwhile
+    pub fn take(&mut self) -> Option<Delivery> {
+        loop {
+            let ready = self.pending & !self.blocked;
+            if ready == 0 {
+                return None;
+            }
+
+            let num = ready.trailing_zeros() as usize;
+            self.pending &= !(1 << num);
+
+            let Ok(sig) = Signal::try_from(num) else {
+                continue;
+            };
+
+            match self.action(sig) {
+                SigAction::Default if sig.ignored_by_default() => continue,
+                SigAction::Default => return Some(Delivery::Terminate(sig)),
+                SigAction::Ignore => continue,
+                SigAction::Handler { handler, restorer } => {
+                    return Some(Delivery::Handle {
+                        sig,
+                        handler,
+                        restorer,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Block `sig` while its handler runs, returning the mask to restore
+    pub fn enter_handler(&mut self, sig: Signal) -> u64 {
+        let old = self.blocked;
+        self.blocked |= sig.mask() & !Signal::Kill.mask();
+        old
+    }
+
+    pub fn restore_blocked(&mut self, blocked: u64) {
+        self.blocked = blocked & !Signal::Kill.mask();
+    }
+}
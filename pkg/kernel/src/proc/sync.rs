@@ -1,7 +1,10 @@
 use super::ProcessId;
 use alloc::collections::*;
+use alloc::sync::{Arc, Weak};
 use spin::Mutex;
 
+pub static SEMAPHORES: Mutex<SemaphoreSet> = Mutex::new(SemaphoreSet::new());
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct SemaphoreId(u32);
 
@@ -16,6 +19,11 @@ impl SemaphoreId {
 pub struct Semaphore {
     count: usize,
     wait_queue: VecDeque<ProcessId>,
+    /// `None` once the owner is reaped, its pid may name another process
+    owner: Option<ProcessId>,
+    /// Whether processes other than the owner may open it by key
+    shared: bool,
+    removed: bool,
 }
 
 /// Semaphore result
@@ -23,16 +31,20 @@ pub struct Semaphore {
 pub enum SemaphoreResult {
     Ok,
     NotExist,
+    WouldBlock,
     Block(ProcessId),
     WakeUp(ProcessId),
 }
 
 impl Semaphore {
     /// Create a new semaphore
-    pub fn new(value: usize) -> Self {
+    pub fn new(value: usize, owner: ProcessId, shared: bool) -> Self {
         Self {
             count: value,
             wait_queue: VecDeque::new(),
+            owner: Some(owner),
+            shared,
+            removed: false,
         }
     }
 
@@ -41,6 +53,9 @@ impl Semaphore {
     /// if the count is 0, then push the process into the wait queue
     /// else decrease the count and return Ok
     pub fn wait(&mut self, pid: ProcessId) -> SemaphoreResult {
+        if self.removed {
+            return SemaphoreResult::NotExist;
+        }
         // FIXME: if the count is 0, then push pid into the wait queue
         if self.count <= 0{
             self.wait_queue.push_back(pid);
@@ -51,11 +66,34 @@ impl Semaphore {
         SemaphoreResult::Ok
     }
 
+    /// Decrease the count if it is not 0, never blocks
+    pub fn try_wait(&mut self) -> SemaphoreResult {
+        if self.removed {
+            SemaphoreResult::NotExist
+        } else if self.count == 0 {
+            SemaphoreResult::WouldBlock
+        } else {
+            self.count -= 1;
+            SemaphoreResult::Ok
+        }
+    }
+
+    /// Take `pid` out of the wait queue, returning whether it was waiting
+    pub fn cancel_wait(&mut self, pid: ProcessId) -> bool {
+        match self.wait_queue.iter().position(|&waiter| waiter == pid) {
+            Some(idx) => self.wait_queue.remove(idx).is_some(),
+            None => false,
+        }
+    }
+
     /// Signal the semaphore (release/up/verhogen)
     ///
     /// if the wait queue is not empty, then pop a process from the wait queue
     /// else increase the count
     pub fn signal(&mut self) -> SemaphoreResult {
+        if self.removed {
+            return SemaphoreResult::NotExist;
+        }
         // FIXME: if the wait queue is not empty
         //          pop a process from the wait queue
         if self.wait_queue.is_empty() {
@@ -66,40 +104,125 @@ impl Semaphore {
         return SemaphoreResult::WakeUp(pid);
         // FIXME: else increase the count and return Ok
     }
+
+    /// Mark the semaphore as removed, returning the processes to wake up
+    fn remove(&mut self) -> VecDeque<ProcessId> {
+        self.removed = true;
+        core::mem::take(&mut self.wait_queue)
+    }
 }
 
+/// The semaphores of the system by key
+///
+/// Processes keep the semaphores they create, open or inherit by `fork`
+/// alive, a semaphore is dropped after the last of them lets it go.
 #[derive(Debug, Default)]
 pub struct SemaphoreSet {
-    sems: BTreeMap<SemaphoreId, Mutex<Semaphore>>,
+    sems: BTreeMap<SemaphoreId, Weak<Mutex<Semaphore>>>,
 }
 
 impl SemaphoreSet {
-    pub fn insert(&mut self, key: u32, value: usize) -> bool {
+    pub const fn new() -> Self {
+        Self {
+            sems: BTreeMap::new(),
+        }
+    }
+
+    fn get(&self, sid: &SemaphoreId) -> Option<Arc<Mutex<Semaphore>>> {
+        self.sems.get(sid).and_then(Weak::upgrade)
+    }
+
+    pub fn insert(
+        &mut self,
+        key: u32,
+        value: usize,
+        owner: ProcessId,
+        shared: bool,
+    ) -> Option<Arc<Mutex<Semaphore>>> {
         trace!("Sem Insert: <{:#x}>{}", key, value);
 
-        // FIXME: insert a new semaphore into the sems
-        //          use `insert(/* ... */).is_none()`
-        self.sems.insert(SemaphoreId::new(key), Semaphore::new(value).into()).is_none()
+        let sid = SemaphoreId::new(key);
+        if self.get(&sid).is_some() {
+            return None;
+        }
+
+        let sem = Arc::new(Mutex::new(Semaphore::new(value, owner, shared)));
+        self.sems.insert(sid, Arc::downgrade(&sem));
+        Some(sem)
+    }
+
+    /// Open the semaphore of `key`, if it is shared or owned by `pid`
+    pub fn open(&self, key: u32, pid: ProcessId) -> Option<Arc<Mutex<Semaphore>>> {
+        trace!("Sem Open: <{:#x}>", key);
+
+        self.get(&SemaphoreId::new(key)).filter(|sem| {
+            let sem = sem.lock();
+            sem.shared || sem.owner == Some(pid)
+        })
     }
 
-    pub fn remove(&mut self, key: u32) -> bool {
+    /// Remove the semaphore owned by `pid` from the namespace,
+    /// returning the processes waiting on it
+    pub fn remove(&mut self, key: u32, pid: ProcessId) -> Option<VecDeque<ProcessId>> {
         trace!("Sem Remove: <{:#x}>", key);
-        // print!("mkknk");
 
-        // FIXME: remove the semaphore from the sems
-        //          use `remove(/* ... */).is_some()`
-        self.sems.remove(&SemaphoreId::new(key)).is_some()
+        let sid = SemaphoreId::new(key);
+        let sem = self.get(&sid)?;
+        let mut sem = sem.lock();
+        if sem.owner != Some(pid) {
+            return None;
+        }
+
+        self.sems.remove(&sid);
+        Some(sem.remove())
+    }
+
+    /// Drop the reaped `pid` from the wait queues and the owners
+    pub fn forget(&mut self, pid: ProcessId) {
+        self.sems.retain(|_, sem| sem.strong_count() > 0);
+
+        for sem in self.sems.values().filter_map(Weak::upgrade) {
+            let mut sem = sem.lock();
+            sem.cancel_wait(pid);
+            if sem.owner == Some(pid) {
+                sem.owner = None;
+            }
+        }
+    }
+}
+
+/// The semaphores a process can use, by key
+#[derive(Debug, Clone, Default)]
+pub struct SemaphoreHandles {
+    sems: BTreeMap<u32, Arc<Mutex<Semaphore>>>,
+}
+
+impl SemaphoreHandles {
+    pub fn insert(&mut self, key: u32, sem: Arc<Mutex<Semaphore>>) {
+        self.sems.insert(key, sem);
+    }
+
+    pub fn close(&mut self, key: u32) -> bool {
+        self.sems.remove(&key).is_some()
+    }
+
+    pub fn get(&self, key: u32) -> Option<Arc<Mutex<Semaphore>>> {
+        self.sems.get(&key).cloned()
+    }
+
+    pub fn try_wait(&self, key: u32) -> SemaphoreResult {
+        match self.sems.get(&key) {
+            Some(sem) => sem.lock().try_wait(),
+            None => SemaphoreResult::NotExist,
+        }
     }
 
     /// Wait the semaphore (acquire/down/proberen)
     pub fn wait(&self, key: u32, pid: ProcessId) -> SemaphoreResult {
-        let sid = SemaphoreId::new(key);
-        // println!("{:?} {:?}",self.sems,sid);
-
         // FIXME: try get the semaphore from the sems
         //         then do it's operation
         // FIXME: return NotExist if the semaphore is not exist
-        if let Some(sem) = self.sems.get(&sid) {
+        if let Some(sem) = self.sems.get(&key) {
             sem.lock().wait(pid)
         } else {
             SemaphoreResult::NotExist
@@ -108,12 +231,10 @@ impl SemaphoreSet {
 
     /// Signal the semaphore (release/up/verhogen)
     pub fn signal(&self, key: u32) -> SemaphoreResult {
-        let sid = SemaphoreId::new(key);
-
         // FIXME: try get the semaphore from the sems
         //         then do it's operation
         // FIXME: return NotExist if the semaphore is not exist
-        if let Some(sem) = self.sems.get(&sid) {
+        if let Some(sem) = self.sems.get(&key) {
             sem.lock().signal()
         } else {
             SemaphoreResult::NotExist
@@ -0,0 +1,57 @@
+use super::{manager::get_process_manager, sync::Semaphore, ProcessId};
+use alloc::{collections::BTreeMap, sync::Weak, vec::Vec};
+use spin::Mutex;
+use syscall_def::SemError;
+
+/// Deadlines of the processes in a timed wait, checked on every clock tick
+static TIMERS: Mutex<BTreeMap<ProcessId, Timer>> = Mutex::new(BTreeMap::new());
+
+/// A process blocks on one thing at a time, so it has one timer at most
+#[derive(Debug)]
+struct Timer {
+    /// In `clock::uptime_ms`
+    deadline: u64,
+    sem: Weak<Mutex<Semaphore>>,
+}
+
+/// Time out the wait of `pid` on `sem` at `deadline`
+///
+/// A pending timer is kept as is, so a wait run again after
+/// being blocked does not move its deadline forward.
+pub fn add(pid: ProcessId, deadline: u64, sem: Weak<Mutex<Semaphore>>) {
+    TIMERS.lock().entry(pid).or_insert(Timer { deadline, sem });
+}
+
+/// Drop the timer of `pid`, as its wait ends in time
+pub fn cancel(pid: ProcessId) {
+    TIMERS.lock().remove(&pid);
+}
+
+/// Wake up the processes whose deadline is passed with `SemError::TimedOut`
+pub fn expire() {
+    let mut timers = TIMERS.lock();
+    if timers.is_empty() {
+        return;
+    }
+
+    let now = crate::clock::uptime_ms();
+    let expired: Vec<ProcessId> = timers
+        .iter()
+        .filter(|(_, timer)| timer.deadline <= now)
+        .map(|(pid, _)| *pid)
+        .collect();
+
+    let manager = get_process_manager();
+    for pid in expired {
+        let timer = timers.remove(&pid).unwrap();
+        // it may be signaled while the timer is due
+        let waiting = timer
+            .sem
+            .upgrade()
+            .is_some_and(|sem| sem.lock().cancel_wait(pid));
+
+        if waiting {
+            manager.wake_up(pid, Some(SemError::TimedOut as isize));
+        }
+    }
+}
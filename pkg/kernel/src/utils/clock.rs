@@ -1,14 +1,53 @@
 use boot::BootInfo;
 use boot::RuntimeServices;
 use boot::Time;
+use core::arch::x86_64::_rdtsc;
+use core::sync::atomic::{AtomicU64, Ordering};
+use x86_64::instructions::port::Port;
 
 once_mutex!(pub TIMER: UefiRuntime);
 
+/// TSC cycles per millisecond, measured at boot
+static TSC_PER_MS: AtomicU64 = AtomicU64::new(1);
+
 pub fn init(boot_info: &'static boot::BootInfo) {
     init_TIMER(unsafe { UefiRuntime::new(boot_info) });
+    TSC_PER_MS.store(calibrate_tsc().max(1), Ordering::Relaxed);
     info!("Timer Initialized.");
 }
 
+/// Count the TSC cycles of one millisecond, timed by PIT channel 2
+fn calibrate_tsc() -> u64 {
+    const PIT_HZ: u64 = 1_193_182;
+    const SAMPLE_MS: u64 = 10;
+
+    let mut gate = Port::<u8>::new(0x61);
+    let mut command = Port::<u8>::new(0x43);
+    let mut data = Port::<u8>::new(0x42);
+    let count = PIT_HZ * SAMPLE_MS / 1000;
+
+    unsafe {
+        // enable the gate of channel 2 with the speaker off
+        let old = gate.read();
+        gate.write((old & !0x02) | 0x01);
+
+        // channel 2, low then high byte, interrupt on terminal count
+        command.write(0b1011_0000);
+        data.write(count as u8);
+        data.write((count >> 8) as u8);
+
+        let start = _rdtsc();
+        // the output of channel 2 goes high at terminal count
+        while gate.read() & 0x20 == 0 {
+            core::hint::spin_loop();
+        }
+        let end = _rdtsc();
+
+        gate.write(old);
+        (end - start) / SAMPLE_MS
+    }
+}
+
 pub struct UefiRuntime {
     runtime_service: &'static RuntimeServices,
 }
@@ -24,4 +63,22 @@ impl UefiRuntime {
         self.runtime_service.get_time().unwrap()
     }
 }
-guard_access_fn!(pub get_timer(TIMER: UefiRuntime));
\ No newline at end of file
+guard_access_fn!(pub get_timer(TIMER: UefiRuntime));
+
+/// Milliseconds since the TSC was reset, which never go back,
+/// for deadlines
+pub fn uptime_ms() -> u64 {
+    let tsc = unsafe { _rdtsc() };
+    tsc / TSC_PER_MS.load(Ordering::Relaxed)
+}
+
+/// Milliseconds since the start of the month, from the UEFI clock,
+/// which wraps and may be set back, so only for `sys_time`
+pub fn now_ms() -> u64 {
+    let time = get_timer_for_sure().get_time();
+    time.nanosecond() as u64 / 1_000_000
+        + time.second() as u64 * 1000
+        + time.minute() as u64 * 60 * 1000
+        + time.hour() as u64 * 3600 * 1000
+        + time.day() as u64 * 24 * 3600 * 1000
+}
@@ -6,13 +6,13 @@ mod regs;
 pub mod clock;
 pub mod func;
 pub mod logger;
+pub mod pipe;
 pub mod resource;
 
 pub use macros::*;
 pub use regs::*;
 
 use crate::proc::{*};
-use self::manager::get_process_manager;
 use alloc::format;
 pub const fn get_ascii_header() -> &'static str {
     concat!(
@@ -56,8 +56,8 @@ fn wait(pid: ProcessId) {
 
         // HINT: it's better to use the exit code
         /* FIXME: is the process exited? */
-        let maneger = get_process_manager();
-        if let None = maneger.check_proc(&pid) {
+        // the kernel reaps its children as they exit
+        if crate::proc::still_alive(pid) {
             x86_64::instructions::hlt();
         } else {
             break;
@@ -0,0 +1,255 @@
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    string::String,
+    sync::{Arc, Weak},
+};
+use spin::Mutex;
+
+use crate::proc::{manager::get_process_manager, ProcessId};
+
+/// Bytes a pipe holds before writers have to wait
+const PIPE_CAPACITY: usize = 4096;
+
+/// The pipes of the named FIFOs in use, by absolute path
+static FIFOS: Mutex<BTreeMap<String, Weak<Mutex<Pipe>>>> = Mutex::new(BTreeMap::new());
+
+/// The pipe each blocked reader or writer waits on,
+/// an entry may stay after the wait, until the next one replaces it
+static WAITING: Mutex<BTreeMap<ProcessId, Weak<Mutex<Pipe>>>> = Mutex::new(BTreeMap::new());
+
+/// A ring buffer shared by the two ends of a pipe
+#[derive(Debug)]
+pub struct Pipe {
+    buf: VecDeque<u8>,
+    readers: usize,
+    writers: usize,
+    /// A FIFO is opened one end at a time, the first reader or writer
+    /// waits for the other side instead of seeing EOF or a broken pipe
+    had_readers: bool,
+    had_writers: bool,
+    read_waiters: VecDeque<ProcessId>,
+    write_waiters: VecDeque<ProcessId>,
+    /// The path of a FIFO, whose entry in `FIFOS` goes with the last end
+    path: Option<String>,
+}
+
+/// One end of a pipe, the pipe is shared by all ends
+///
+/// Ends are counted, so readers see EOF once every write end
+/// is dropped and writers fail once every read end is dropped.
+#[derive(Debug)]
+pub enum PipeEnd {
+    Read(Arc<Mutex<Pipe>>),
+    Write(Arc<Mutex<Pipe>>),
+}
+
+/// Create a pipe, returning its read and write ends
+pub fn pipe() -> (PipeEnd, PipeEnd) {
+    let pipe = Arc::new(Mutex::new(Pipe::new(1, 1, None)));
+
+    (PipeEnd::Read(pipe.clone()), PipeEnd::Write(pipe))
+}
+
+/// Open an end of the named FIFO at `path`
+///
+/// All the ends opened by path share one pipe while any of them is open.
+pub fn open_fifo(path: &str, write: bool) -> PipeEnd {
+    let pipe = {
+        let mut fifos = FIFOS.lock();
+        match fifos.get(path).and_then(Weak::upgrade) {
+            Some(pipe) => pipe,
+            None => {
+                let pipe = Arc::new(Mutex::new(Pipe::new(0, 0, Some(path.into()))));
+                fifos.insert(path.into(), Arc::downgrade(&pipe));
+                pipe
+            }
+        }
+    };
+
+    let mut inner = pipe.lock();
+
+    // the other side may be waiting for the first peer
+    if write {
+        inner.writers += 1;
+        inner.had_writers = true;
+        Pipe::wake_all(&mut inner.read_waiters);
+    } else {
+        inner.readers += 1;
+        inner.had_readers = true;
+        Pipe::wake_all(&mut inner.write_waiters);
+    }
+
+    drop(inner);
+
+    if write {
+        PipeEnd::Write(pipe)
+    } else {
+        PipeEnd::Read(pipe)
+    }
+}
+
+/// Drop the reaped `pid` from the waiters of the pipe it waited on
+pub fn forget(pid: ProcessId) {
+    let pipe = WAITING.lock().remove(&pid).and_then(|pipe| pipe.upgrade());
+    if let Some(pipe) = pipe {
+        let mut pipe = pipe.lock();
+        pipe.read_waiters.retain(|&waiter| waiter != pid);
+        pipe.write_waiters.retain(|&waiter| waiter != pid);
+    }
+}
+
+/// Forget the FIFO removed from `path`, opening a new one there
+/// must not reach the pipe of the old one
+pub fn remove_fifo(path: &str) {
+    FIFOS.lock().remove(path);
+}
+
+/// Follow the FIFO moved from `src` to `dst`, replacing any at `dst`
+pub fn move_fifo(src: &str, dst: &str) {
+    let mut fifos = FIFOS.lock();
+    fifos.remove(dst);
+
+    if let Some(fifo) = fifos.remove(src) {
+        if let Some(pipe) = fifo.upgrade() {
+            pipe.lock().path = Some(dst.into());
+            fifos.insert(dst.into(), fifo);
+        }
+    }
+}
+
+impl Pipe {
+    fn new(readers: usize, writers: usize, path: Option<String>) -> Self {
+        Self {
+            buf: VecDeque::with_capacity(PIPE_CAPACITY),
+            readers,
+            writers,
+            had_readers: readers > 0,
+            had_writers: writers > 0,
+            read_waiters: VecDeque::new(),
+            write_waiters: VecDeque::new(),
+            path,
+        }
+    }
+
+    fn wake_all(waiters: &mut VecDeque<ProcessId>) {
+        let manager = get_process_manager();
+        for pid in waiters.drain(..) {
+            manager.wake_up(pid, None);
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.buf.len());
+
+        for (dst, src) in buf.iter_mut().zip(self.buf.drain(..len)) {
+            *dst = src;
+        }
+
+        if len > 0 {
+            Self::wake_all(&mut self.write_waiters);
+        }
+
+        len
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Option<usize> {
+        if self.readers == 0 {
+            return None;
+        }
+
+        let len = buf.len().min(PIPE_CAPACITY - self.buf.len());
+        self.buf.extend(&buf[..len]);
+
+        if len > 0 {
+            Self::wake_all(&mut self.read_waiters);
+        }
+
+        Some(len)
+    }
+}
+
+impl PipeEnd {
+    /// Read what is in the pipe, 0 at EOF
+    pub fn read(&self, buf: &mut [u8]) -> Option<usize> {
+        match self {
+            PipeEnd::Read(pipe) => Some(pipe.lock().read(buf)),
+            PipeEnd::Write(_) => None,
+        }
+    }
+
+    /// Write as much as fits, `None` if no reader is left
+    pub fn write(&self, buf: &[u8]) -> Option<usize> {
+        match self {
+            PipeEnd::Write(pipe) => pipe.lock().write(buf),
+            PipeEnd::Read(_) => None,
+        }
+    }
+
+    /// Check whether a read has to wait for data,
+    /// if so `pid` is woken up once there is data or EOF
+    pub fn wait_read(&self, pid: ProcessId) -> bool {
+        match self {
+            PipeEnd::Read(arc) => {
+                let mut pipe = arc.lock();
+                let wait = pipe.buf.is_empty() && (pipe.writers > 0 || !pipe.had_writers);
+                if wait {
+                    pipe.read_waiters.push_back(pid);
+                    WAITING.lock().insert(pid, Arc::downgrade(arc));
+                }
+                wait
+            }
+            PipeEnd::Write(_) => false,
+        }
+    }
+
+    /// Check whether a write has to wait for space,
+    /// if so `pid` is woken up once there is space or no reader
+    pub fn wait_write(&self, pid: ProcessId) -> bool {
+        match self {
+            PipeEnd::Write(arc) => {
+                let mut pipe = arc.lock();
+                let wait = (pipe.buf.len() == PIPE_CAPACITY && pipe.readers > 0)
+                    || !pipe.had_readers;
+                if wait {
+                    pipe.write_waiters.push_back(pid);
+                    WAITING.lock().insert(pid, Arc::downgrade(arc));
+                }
+                wait
+            }
+            PipeEnd::Read(_) => false,
+        }
+    }
+}
+
+impl Drop for PipeEnd {
+    fn drop(&mut self) {
+        let (PipeEnd::Read(arc) | PipeEnd::Write(arc)) = &*self;
+        let mut pipe = arc.lock();
+
+        match &*self {
+            PipeEnd::Read(_) => {
+                pipe.readers -= 1;
+                if pipe.readers == 0 {
+                    Pipe::wake_all(&mut pipe.write_waiters);
+                }
+            }
+            PipeEnd::Write(_) => {
+                pipe.writers -= 1;
+                if pipe.writers == 0 {
+                    Pipe::wake_all(&mut pipe.read_waiters);
+                }
+            }
+        }
+
+        // the next open of the FIFO starts a new pipe
+        if pipe.readers == 0 && pipe.writers == 0 {
+            if let Some(path) = pipe.path.take() {
+                drop(pipe);
+                let mut fifos = FIFOS.lock();
+                if fifos.get(&path).is_some_and(|fifo| fifo.as_ptr() == Arc::as_ptr(arc)) {
+                    fifos.remove(&path);
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,16 @@
-use alloc::{collections::BTreeMap, string::String};
+use alloc::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    string::String,
+    sync::Arc,
+};
 use spin::Mutex;
-use storage::FileHandle;
+use storage::{FileHandle, Metadata, SeekFrom};
+use syscall_def::{OpenFlags, Stat};
 
-use crate::input::try_pop_key;
+use crate::filesystem::{dirent_of, stat_of};
+use crate::input::{try_pop_key, wait_key};
+use crate::pipe::PipeEnd;
+use crate::proc::ProcessId;
 
 #[derive(Debug, Clone)]
 pub enum StdIO {
@@ -11,15 +19,23 @@ pub enum StdIO {
     Stderr,
 }
 
-#[derive(Debug)]
+/// The file descriptor table of a process
+///
+/// Descriptors duplicated by `dup` share the same resource,
+/// which is dropped once the last of them is closed.
+/// Cloning the set shares every resource, as `fork` does.
+#[derive(Debug, Clone)]
 pub struct ResourceSet {
-    pub handles: BTreeMap<u8, Mutex<Resource>>,
+    pub handles: BTreeMap<u8, Arc<Mutex<Resource>>>,
+    /// Descriptors left out of spawned processes
+    cloexec: BTreeSet<u8>,
 }
 
 impl Default for ResourceSet {
     fn default() -> Self {
         let mut res = Self {
             handles: BTreeMap::new(),
+            cloexec: BTreeSet::new(),
         };
 
         res.open(Resource::Console(StdIO::Stdin));
@@ -31,16 +47,64 @@ impl Default for ResourceSet {
 }
 
 impl ResourceSet {
-    pub fn open(&mut self, res: Resource) -> u8 {
-        let fd = self.handles.len() as u8;
-        self.handles.insert(fd, Mutex::new(res));
-        fd
+    /// The lowest descriptor not in use
+    fn free_fd(&self) -> Option<u8> {
+        (0..=u8::MAX).find(|fd| !self.handles.contains_key(fd))
+    }
+
+    /// Open the resource at the lowest free descriptor,
+    /// `None` if all descriptors are in use
+    pub fn open(&mut self, res: Resource) -> Option<u8> {
+        let fd = self.free_fd()?;
+        self.handles.insert(fd, Arc::new(Mutex::new(res)));
+        Some(fd)
     }
 
     pub fn close(&mut self, fd: u8) -> bool {
+        self.cloexec.remove(&fd);
         self.handles.remove(&fd).is_some()
     }
 
+    /// Leave `fd` out of the processes spawned from now on
+    pub fn set_cloexec(&mut self, fd: u8) {
+        if self.handles.contains_key(&fd) {
+            self.cloexec.insert(fd);
+        }
+    }
+
+    /// The descriptor table a spawned process starts with
+    pub fn spawn_copy(&self) -> Self {
+        Self {
+            handles: self
+                .handles
+                .iter()
+                .filter(|(fd, _)| !self.cloexec.contains(fd))
+                .map(|(&fd, res)| (fd, res.clone()))
+                .collect(),
+            cloexec: BTreeSet::new(),
+        }
+    }
+
+    pub fn get(&self, fd: u8) -> Option<Arc<Mutex<Resource>>> {
+        self.handles.get(&fd).cloned()
+    }
+
+    /// Duplicate `fd` to the lowest free descriptor
+    pub fn dup(&mut self, fd: u8) -> Option<u8> {
+        let res = self.handles.get(&fd)?.clone();
+        let new_fd = self.free_fd()?;
+        self.handles.insert(new_fd, res);
+        Some(new_fd)
+    }
+
+    /// Duplicate `old_fd` to `new_fd`, closing `new_fd` first if it is open
+    pub fn dup2(&mut self, old_fd: u8, new_fd: u8) -> Option<u8> {
+        let res = self.handles.get(&old_fd)?.clone();
+        self.cloexec.remove(&new_fd);
+        self.handles.insert(new_fd, res);
+        Some(new_fd)
+    }
+
     pub fn read(&self, fd: u8, buf: &mut [u8]) -> isize {
         if let Some(count) = self.handles.get(&fd).and_then(|h| h.lock().read(buf)) {
             count as isize
@@ -56,12 +120,43 @@ impl ResourceSet {
             -1
         }
     }
+
+    pub fn seek(&self, fd: u8, pos: SeekFrom) -> isize {
+        if let Some(offset) = self.handles.get(&fd).and_then(|h| h.lock().seek(pos)) {
+            offset as isize
+        } else {
+            -1
+        }
+    }
+
+    pub fn pread(&self, fd: u8, buf: &mut [u8], offset: usize) -> isize {
+        if let Some(count) = self.handles.get(&fd).and_then(|h| h.lock().pread(buf, offset)) {
+            count as isize
+        } else {
+            -1
+        }
+    }
+
+    pub fn getdents(&self, fd: u8, buf: &mut [u8]) -> isize {
+        if let Some(count) = self.handles.get(&fd).and_then(|h| h.lock().getdents(buf)) {
+            count as isize
+        } else {
+            -1
+        }
+    }
+
+    pub fn fstat(&self, fd: u8) -> Option<Stat> {
+        self.handles.get(&fd).and_then(|h| h.lock().fstat())
+    }
 }
 
 #[derive(Debug)]
 pub enum Resource {
     Console(StdIO),
-    File(FileHandle),
+    File(FileHandle, OpenFlags),
+    /// The entries of a directory not yet returned by `getdents`
+    Dir(VecDeque<Metadata>),
+    Pipe(PipeEnd),
     Null,
 }
 
@@ -84,12 +179,17 @@ impl Resource {
                 }
                 _ => None,
             },
-            Resource::File(f) => {
+            Resource::File(f, flags) => {
+                if !flags.contains(OpenFlags::READ) {
+                    return None;
+                }
                 match f.read(buf) {
                     Ok(ret) => Some(ret),
                     _ => None
                 }
             },
+            Resource::Dir(_) => None,
+            Resource::Pipe(pipe) => pipe.read(buf),
             Resource::Null => Some(0),
         }
     }
@@ -107,8 +207,98 @@ impl Resource {
                     Some(buf.len())
                 }
             },
-            Resource::File(_) => Some(0),
+            Resource::File(f, flags) => {
+                if !flags.contains(OpenFlags::WRITE) {
+                    return None;
+                }
+                f.write(buf).ok()
+            }
+            Resource::Dir(_) => None,
+            Resource::Pipe(pipe) => pipe.write(buf),
             Resource::Null => Some(buf.len()),
         }
     }
+
+    /// Check whether a read has to wait, registering `pid` to be woken up
+    ///
+    /// Pipes and the console block, others return what they have at once.
+    pub fn wait_read(&self, pid: ProcessId) -> bool {
+        match self {
+            Resource::Console(StdIO::Stdin) => wait_key(pid),
+            Resource::Pipe(pipe) => pipe.wait_read(pid),
+            _ => false,
+        }
+    }
+
+    /// Check whether a write has to wait, registering `pid` to be woken up
+    pub fn wait_write(&self, pid: ProcessId) -> bool {
+        match self {
+            Resource::Pipe(pipe) => pipe.wait_write(pid),
+            _ => false,
+        }
+    }
+
+    /// Only files are seekable
+    pub fn seek(&mut self, pos: SeekFrom) -> Option<usize> {
+        match self {
+            Resource::File(f, _) => f.seek(pos).ok(),
+            _ => None,
+        }
+    }
+
+    /// Read at `offset` without moving the offset of the file
+    pub fn pread(&mut self, buf: &mut [u8], offset: usize) -> Option<usize> {
+        match self {
+            Resource::File(f, flags) if flags.contains(OpenFlags::READ) => {
+                let current = f.seek(SeekFrom::Current(0)).ok()?;
+                f.seek(SeekFrom::Start(offset)).ok()?;
+                let ret = f.read(buf);
+                f.seek(SeekFrom::Start(current)).ok()?;
+                ret.ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Only files have metadata, the length is read from the file
+    /// since it may have been written after being opened
+    pub fn fstat(&mut self) -> Option<Stat> {
+        match self {
+            Resource::File(f, _) => {
+                let current = f.seek(SeekFrom::Current(0)).ok()?;
+                let len = f.seek(SeekFrom::End(0)).ok()?;
+                f.seek(SeekFrom::Start(current)).ok()?;
+
+                let mut stat = stat_of(&f.meta);
+                stat.size = len as u64;
+                Some(stat)
+            }
+            _ => None,
+        }
+    }
+
+    /// Fill `buf` with as many `Dirent` records as fit
+    ///
+    /// Returns 0 once all entries are returned,
+    /// `None` if `buf` cannot hold even the next entry.
+    pub fn getdents(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let entries = match self {
+            Resource::Dir(entries) => entries,
+            _ => return None,
+        };
+
+        let mut written = 0;
+
+        while let Some(meta) = entries.front() {
+            match dirent_of(meta).encode(&meta.name, &mut buf[written..]) {
+                Some(len) => written += len,
+                None if written == 0 => return None,
+                None => break,
+            }
+            entries.pop_front();
+        }
+
+        Some(written)
+    }
 }
+
@@ -0,0 +1,206 @@
+use crate::syscall::*;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use syscall_def::{Dirent, FileKind};
+
+/// An open file, closed when dropped
+#[derive(Debug)]
+pub struct File {
+    fd: u8,
+}
+
+impl File {
+    /// Open an existing file for reading
+    pub fn open(path: &str) -> Option<Self> {
+        Self::open_with(path, OpenFlags::READ)
+    }
+
+    /// Open a file for writing, creating or truncating it
+    pub fn create(path: &str) -> Option<Self> {
+        Self::open_with(
+            path,
+            OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+        )
+    }
+
+    /// Open a file for appending, creating it if missing
+    pub fn append(path: &str) -> Option<Self> {
+        Self::open_with(
+            path,
+            OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::APPEND,
+        )
+    }
+
+    pub fn open_with(path: &str, flags: OpenFlags) -> Option<Self> {
+        sys_open(path, flags).map(|fd| Self { fd })
+    }
+
+    pub fn fd(&self) -> u8 {
+        self.fd
+    }
+
+    /// Read into `buf`, returns 0 at the end of the file
+    pub fn read(&mut self, buf: &mut [u8]) -> Option<usize> {
+        sys_read(self.fd, buf)
+    }
+
+    /// Read the rest of the file into `buf`
+    pub fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Option<usize> {
+        let mut chunk = [0u8; 512];
+        let mut total = 0;
+
+        loop {
+            match self.read(&mut chunk)? {
+                0 => return Some(total),
+                n => {
+                    buf.extend_from_slice(&chunk[..n]);
+                    total += n;
+                }
+            }
+        }
+    }
+
+    /// Read the rest of the file as UTF-8, invalid sequences are replaced
+    pub fn read_to_string(&mut self, buf: &mut String) -> Option<usize> {
+        let mut bytes = Vec::new();
+        let len = self.read_to_end(&mut bytes)?;
+        buf.push_str(&String::from_utf8_lossy(&bytes));
+        Some(len)
+    }
+
+    pub fn write(&mut self, buf: &[u8]) -> Option<usize> {
+        sys_write(self.fd, buf)
+    }
+
+    pub fn write_all(&mut self, mut buf: &[u8]) -> Option<()> {
+        while !buf.is_empty() {
+            match self.write(buf)? {
+                0 => return None,
+                n => buf = &buf[n..],
+            }
+        }
+        Some(())
+    }
+
+    /// Move the offset, returns the new offset from the start
+    pub fn seek(&mut self, offset: isize, whence: Whence) -> Option<usize> {
+        sys_seek(self.fd, offset, whence)
+    }
+
+    /// Read at `offset` without moving the offset
+    pub fn read_at(&self, buf: &mut [u8], offset: usize) -> Option<usize> {
+        sys_pread(self.fd, buf, offset)
+    }
+
+    pub fn metadata(&self) -> Option<Stat> {
+        sys_fstat(self.fd)
+    }
+}
+
+/// Get the metadata of the file or directory at `path`
+pub fn metadata(path: &str) -> Option<Stat> {
+    sys_file_stat(path)
+}
+
+/// Return `true` if a file or directory exists at `path`
+pub fn exists(path: &str) -> bool {
+    metadata(path).is_some()
+}
+
+/// Create a named FIFO at `path`, unrelated processes opening it
+/// for reading and for writing share one pipe
+pub fn mkfifo(path: &str) -> bool {
+    sys_mkfifo(path)
+}
+
+/// Remove the file at `path`
+pub fn remove_file(path: &str) -> bool {
+    sys_unlink(path)
+}
+
+/// Move the file at `src` to `dst`, replacing any file there
+pub fn rename(src: &str, dst: &str) -> bool {
+    sys_rename(src, dst)
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        sys_close(self.fd);
+    }
+}
+
+/// An entry returned by `ReadDir`
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub kind: FileKind,
+    /// Length in bytes, 0 for directories
+    pub len: usize,
+    /// Seconds since the Unix epoch, if known
+    pub created: Option<i64>,
+    pub modified: Option<i64>,
+    pub accessed: Option<i64>,
+}
+
+impl DirEntry {
+    pub fn is_file(&self) -> bool {
+        self.kind == FileKind::File
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == FileKind::Directory
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.kind == FileKind::Symlink
+    }
+
+    pub fn is_fifo(&self) -> bool {
+        self.kind == FileKind::Fifo
+    }
+}
+
+/// Iterator over the entries of a directory, see `read_dir`
+#[derive(Debug)]
+pub struct ReadDir {
+    dir: File,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+}
+
+/// List the entries of the directory at `path`
+pub fn read_dir(path: &str) -> Option<ReadDir> {
+    let dir = File::open_with(path, OpenFlags::READ | OpenFlags::DIRECTORY)?;
+
+    Some(ReadDir {
+        dir,
+        buf: vec![0; 1024],
+        pos: 0,
+        len: 0,
+    })
+}
+
+impl Iterator for ReadDir {
+    type Item = DirEntry;
+
+    fn next(&mut self) -> Option<DirEntry> {
+        if self.pos >= self.len {
+            self.len = sys_getdents(self.dir.fd(), &mut self.buf)?;
+            self.pos = 0;
+        }
+
+        let (dirent, name) = Dirent::decode(&self.buf[self.pos..self.len])?;
+        self.pos += dirent.reclen as usize;
+
+        Some(DirEntry {
+            name: name.to_string(),
+            kind: dirent.kind,
+            len: dirent.size as usize,
+            created: Dirent::time(dirent.created),
+            modified: Dirent::time(dirent.modified),
+            accessed: Dirent::time(dirent.accessed),
+        })
+    }
+}
@@ -12,16 +12,15 @@ impl Stdin {
         Self
     }
 
+    /// Read a character, `'\x04'` (^D) once the input ends
     pub fn read_key(&self) -> char {
         let mut buffer = [0u8; 4];
         let mut len = 0;
         let mut buf:[u8;1] = [0];
         loop {
-            let ret = sys_read(0,&mut buf);
-            if let Some(l) = ret {
-                if l == 0{
-                    continue;
-                }
+            // reads block until there is input, 0 bytes is the end of it
+            if sys_read(0, &mut buf) != Some(1) {
+                return '\x04';
             }
             buffer[len] = buf[0];
             len += 1;
@@ -81,7 +80,12 @@ impl Stdout {
     }
 
     pub fn write(&self, s: &str) {
-        sys_write(1, s.as_bytes());
+        self.write_bytes(s.as_bytes());
+    }
+
+    /// Write raw bytes, which may not be valid UTF-8
+    pub fn write_bytes(&self, buf: &[u8]) {
+        write_all(1, buf);
     }
 }
 
@@ -91,7 +95,17 @@ impl Stderr {
     }
 
     pub fn write(&self, s: &str) {
-        sys_write(2, s.as_bytes());
+        write_all(2, s.as_bytes());
+    }
+}
+
+/// Write the whole of `buf`, going on after short writes
+fn write_all(fd: u8, mut buf: &[u8]) {
+    while !buf.is_empty() {
+        match sys_write(fd, buf) {
+            Some(len) if len > 0 => buf = &buf[len..],
+            _ => break,
+        }
     }
 }
 
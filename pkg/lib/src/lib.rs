@@ -14,6 +14,8 @@ pub mod io;
 pub mod allocator;
 pub extern crate alloc;
 
+pub mod fs;
+pub mod signal;
 pub mod syscall;
 pub mod sync;
 
@@ -0,0 +1,45 @@
+use crate::syscall::*;
+use syscall_def::{Syscall, SIG_DFL, SIG_IGN};
+
+/// A signal handler, called with the signal it handles
+pub type SigHandlerFn = extern "C" fn(Signal);
+
+#[derive(Clone, Copy)]
+pub enum SigHandler {
+    Default,
+    Ignore,
+    Handler(SigHandlerFn),
+}
+
+// handlers return here to get back to the interrupted code
+core::arch::global_asm!(
+    ".global __sigreturn",
+    "__sigreturn:",
+    "mov rax, {sigreturn}",
+    "int 0x80",
+    sigreturn = const Syscall::SigReturn as usize,
+);
+
+extern "C" {
+    fn __sigreturn();
+}
+
+/// Set how the process handles `sig`, which fails for `Signal::Kill`
+pub fn signal(sig: Signal, handler: SigHandler) -> bool {
+    let handler = match handler {
+        SigHandler::Default => SIG_DFL,
+        SigHandler::Ignore => SIG_IGN,
+        SigHandler::Handler(f) => f as usize,
+    };
+    sys_sigaction(sig, handler, __sigreturn as *const () as usize)
+}
+
+/// Block `set` of signals, returning the old mask
+pub fn block(set: u64) -> u64 {
+    sys_sigprocmask(SigHow::Block, set)
+}
+
+/// Unblock `set` of signals, returning the old mask
+pub fn unblock(set: u64) -> u64 {
+    sys_sigprocmask(SigHow::Unblock, set)
+}
@@ -1,6 +1,8 @@
 use core::{
+    cell::UnsafeCell,
     hint::spin_loop,
-    sync::atomic::{AtomicBool, Ordering},
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
 };
 
 use crate::*;
@@ -45,7 +47,21 @@ impl Semaphore {
 
     #[inline(always)]
     pub fn init(&self, value: usize) -> bool {
-        sys_new_sem(self.key, value)
+        sys_new_sem(self.key, value, SemFlags::NONE)
+    }
+
+    /// Create the semaphore for unrelated processes to `open` as well
+    #[inline(always)]
+    pub fn init_shared(&self, value: usize) -> bool {
+        sys_new_sem(self.key, value, SemFlags::SHARED)
+    }
+
+    pub fn open(&self) -> bool {
+        sys_open_sem(self.key)
+    }
+
+    pub fn close(&self) -> bool {
+        sys_close_sem(self.key)
     }
 
     /* FIXME: other functions with syscall... */
@@ -57,6 +73,16 @@ impl Semaphore {
         sys_wait(self.key)
     }
 
+    /// Wait only if the semaphore is available right now
+    pub fn try_wait(&self) -> core::result::Result<(), SemError> {
+        sys_try_wait(self.key)
+    }
+
+    /// Wait for `ms` milliseconds at most, `SemError::TimedOut` after that
+    pub fn timed_wait(&self, ms: u64) -> core::result::Result<(), SemError> {
+        sys_timed_wait(self.key, ms)
+    }
+
     pub fn remove(&self) -> bool {
         sys_remove(self.key)
     }
@@ -70,3 +96,297 @@ macro_rules! semaphore_array {
         [ $($crate::sync::Semaphore::new($x),)* ]
     }
 }
+
+/// A lock blocking in the kernel while it is taken, the uncontended
+/// `lock` and `unlock` never leave user space
+///
+/// The state is 0 when unlocked, 1 when locked and 2 when locked
+/// with processes possibly waiting for it.
+pub struct Mutex<T> {
+    state: AtomicU32,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for Mutex<T> {}
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn new(data: T) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        if self
+            .state
+            .compare_exchange(0, 1, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            // mark it as contended, so that the owner wakes us up
+            while self.state.swap(2, Ordering::Acquire) != 0 {
+                sys_futex_wait(&self.state, 2);
+            }
+        }
+
+        MutexGuard { mutex: self }
+    }
+
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.state
+            .compare_exchange(0, 1, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| MutexGuard { mutex: self })
+    }
+
+    fn unlock(&self) {
+        if self.state.swap(0, Ordering::Release) == 2 {
+            sys_futex_wake(&self.state, 1);
+        }
+    }
+}
+
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+/// Wait for a condition under a `Mutex`
+///
+/// Every notification bumps the counter, a waiter only blocks
+/// if no notification comes after it unlocks the mutex.
+pub struct Condvar {
+    counter: AtomicU32,
+}
+
+impl Condvar {
+    pub const fn new() -> Self {
+        Self {
+            counter: AtomicU32::new(0),
+        }
+    }
+
+    /// Unlock the mutex and wait for a notification, then lock it again
+    ///
+    /// It may also wake up spuriously, so check the condition in a loop.
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        let counter = self.counter.load(Ordering::Relaxed);
+        let mutex = guard.mutex;
+        drop(guard);
+
+        sys_futex_wait(&self.counter, counter);
+        mutex.lock()
+    }
+
+    pub fn notify_one(&self) {
+        self.counter.fetch_add(1, Ordering::Relaxed);
+        sys_futex_wake(&self.counter, 1);
+    }
+
+    pub fn notify_all(&self) {
+        self.counter.fetch_add(1, Ordering::Relaxed);
+        sys_futex_wake(&self.counter, usize::MAX);
+    }
+}
+
+/// A lock for many readers or one writer
+///
+/// The low bits of the state are the count of readers, or `WRITER`
+/// when write-locked. `WAITERS` is set while someone waits, as only
+/// then an unlock has to wake them.
+pub struct RwLock<T> {
+    state: AtomicU32,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for RwLock<T> {}
+unsafe impl<T: Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+    const WAITERS: u32 = 1 << 31;
+    const WRITER: u32 = Self::WAITERS - 1;
+
+    pub const fn new(data: T) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        loop {
+            let state = self.state.load(Ordering::Relaxed);
+            let readers = state & Self::WRITER;
+            if readers == Self::WRITER {
+                self.wait(state);
+                continue;
+            }
+
+            assert!(readers + 1 < Self::WRITER, "too many readers of RwLock");
+            if self
+                .state
+                .compare_exchange_weak(state, state + 1, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return RwLockReadGuard { lock: self };
+            }
+        }
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        loop {
+            let state = self.state.load(Ordering::Relaxed);
+            if state & Self::WRITER != 0 {
+                self.wait(state);
+            } else if self
+                .state
+                .compare_exchange_weak(
+                    state,
+                    state | Self::WRITER,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+            {
+                return RwLockWriteGuard { lock: self };
+            }
+        }
+    }
+
+    /// Mark that someone waits, then wait for the state to change
+    fn wait(&self, state: u32) {
+        let state = state | Self::WAITERS;
+        if self.state.fetch_or(Self::WAITERS, Ordering::Relaxed) | Self::WAITERS == state {
+            sys_futex_wait(&self.state, state);
+        }
+    }
+
+    /// Wake up every waiter, those still locked out mark themselves again
+    fn wake(&self) {
+        sys_futex_wake(&self.state, usize::MAX);
+    }
+
+    fn read_unlock(&self) {
+        // the last reader lets the writers in
+        let state = self.state.fetch_sub(1, Ordering::Release);
+        if state & Self::WRITER == 1
+            && state & Self::WAITERS != 0
+            && self
+                .state
+                .compare_exchange(Self::WAITERS, 0, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.wake();
+        }
+    }
+
+    fn write_unlock(&self) {
+        if self.state.swap(0, Ordering::Release) & Self::WAITERS != 0 {
+            self.wake();
+        }
+    }
+}
+
+pub struct RwLockReadGuard<'a, T> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for RwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+pub struct RwLockWriteGuard<'a, T> {
+    lock: &'a RwLock<T>,
+}
+
+impl<T> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for RwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
+
+/// Run an initialization once, others calling meanwhile wait for it
+pub struct Once {
+    state: AtomicU32,
+}
+
+impl Once {
+    const INCOMPLETE: u32 = 0;
+    const RUNNING: u32 = 1;
+    const COMPLETE: u32 = 2;
+
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(Self::INCOMPLETE),
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == Self::COMPLETE
+    }
+
+    pub fn call_once(&self, f: impl FnOnce()) {
+        if self.is_completed() {
+            return;
+        }
+
+        if self
+            .state
+            .compare_exchange(Self::INCOMPLETE, Self::RUNNING, Ordering::Acquire, Ordering::Acquire)
+            .is_ok()
+        {
+            f();
+            self.state.store(Self::COMPLETE, Ordering::Release);
+            sys_futex_wake(&self.state, usize::MAX);
+            return;
+        }
+
+        while !self.is_completed() {
+            sys_futex_wait(&self.state, Self::RUNNING);
+        }
+    }
+}
@@ -1,5 +1,12 @@
+use alloc::{vec, vec::Vec};
+use core::sync::atomic::AtomicU32;
 use syscall_def::Syscall;
 
+pub use syscall_def::{
+    FutexOp, MsgCmd, MsgFlags, OpenFlags, SemError, SemFlags, SigHow, Signal, Stat, WaitFlags,
+    Whence,
+};
+
 #[inline(always)]
 pub fn sys_write(fd: u8, buf: &[u8]) -> Option<usize> {
     let ret = syscall!(
@@ -30,18 +37,174 @@ pub fn sys_read(fd: u8, buf: &mut [u8]) -> Option<usize> {
     }
 }
 
+#[inline(always)]
+pub fn sys_open(path: &str, flags: OpenFlags) -> Option<u8> {
+    let ret = syscall!(
+        Syscall::Open,
+        path.as_ptr() as u64,
+        path.len() as u64,
+        flags.0 as u64
+    ) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some(ret as u8)
+    }
+}
+
+#[inline(always)]
+pub fn sys_close(fd: u8) -> bool {
+    syscall!(Syscall::Close, fd as u64) == 0
+}
+
+#[inline(always)]
+pub fn sys_file_stat(path: &str) -> Option<Stat> {
+    let mut stat = core::mem::MaybeUninit::<Stat>::uninit();
+    let ret = syscall!(
+        Syscall::FileStat,
+        path.as_ptr() as u64,
+        path.len() as u64,
+        stat.as_mut_ptr() as u64
+    );
+    // SAFETY: the kernel fills the whole struct on success
+    (ret == 0).then(|| unsafe { stat.assume_init() })
+}
+
+#[inline(always)]
+pub fn sys_fstat(fd: u8) -> Option<Stat> {
+    let mut stat = core::mem::MaybeUninit::<Stat>::uninit();
+    let ret = syscall!(Syscall::FStat, fd as u64, stat.as_mut_ptr() as u64);
+    // SAFETY: the kernel fills the whole struct on success
+    (ret == 0).then(|| unsafe { stat.assume_init() })
+}
+
+/// Create a pipe, returning the fds of its read and write ends
+///
+/// Only `OpenFlags::CLOEXEC` applies, keeping both ends from spawned apps.
+#[inline(always)]
+pub fn sys_pipe(flags: OpenFlags) -> Option<(u8, u8)> {
+    let mut fds = [0u8; 2];
+    if syscall!(Syscall::Pipe, fds.as_mut_ptr() as u64, flags.0 as u64) == 0 {
+        Some((fds[0], fds[1]))
+    } else {
+        None
+    }
+}
+
+/// Remove the file at `path`
+#[inline(always)]
+pub fn sys_unlink(path: &str) -> bool {
+    syscall!(Syscall::Unlink, path.as_ptr() as u64, path.len() as u64) == 0
+}
+
+/// Move the file at `src` to `dst`, replacing any file there
+#[inline(always)]
+pub fn sys_rename(src: &str, dst: &str) -> bool {
+    syscall!(
+        Syscall::Rename,
+        src.as_ptr() as u64,
+        src.len() as u64,
+        dst.as_ptr() as u64,
+        dst.len() as u64
+    ) == 0
+}
+
+/// Create a named FIFO at `path`, opened like a file to get a pipe end
+#[inline(always)]
+pub fn sys_mkfifo(path: &str) -> bool {
+    syscall!(Syscall::MkFifo, path.as_ptr() as u64, path.len() as u64) == 0
+}
+
+/// Duplicate `fd` to the lowest free descriptor
+#[inline(always)]
+pub fn sys_dup(fd: u8) -> Option<u8> {
+    let ret = syscall!(Syscall::Dup, fd as u64) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some(ret as u8)
+    }
+}
+
+/// Make `new_fd` refer to the same resource as `old_fd`
+#[inline(always)]
+pub fn sys_dup2(old_fd: u8, new_fd: u8) -> Option<u8> {
+    let ret = syscall!(Syscall::Dup2, old_fd as u64, new_fd as u64) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some(ret as u8)
+    }
+}
+
+/// Move the offset of `fd`, returning the new offset from the start
+#[inline(always)]
+pub fn sys_seek(fd: u8, offset: isize, whence: Whence) -> Option<usize> {
+    let ret = syscall!(Syscall::Seek, fd as u64, offset as u64, whence as u64) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some(ret as usize)
+    }
+}
+
+/// Read from `offset` of `fd` without moving its offset
+#[inline(always)]
+pub fn sys_pread(fd: u8, buf: &mut [u8], offset: usize) -> Option<usize> {
+    let ret = syscall!(
+        Syscall::PRead,
+        fd as u64,
+        buf.as_ptr() as u64,
+        buf.len() as u64,
+        offset as u64
+    ) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some(ret as usize)
+    }
+}
+
+/// Fill `buf` with `Dirent` records of the directory `fd`, 0 at the end
+#[inline(always)]
+pub fn sys_getdents(fd: u8, buf: &mut [u8]) -> Option<usize> {
+    let ret = syscall!(
+        Syscall::GetDents,
+        fd as u64,
+        buf.as_ptr() as u64,
+        buf.len() as u64
+    ) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some(ret as usize)
+    }
+}
+
+/// Wait for the child `pid` to exit, -1 if there is no such child
 #[inline(always)]
 pub fn sys_wait_pid(pid: u16) -> isize {
-    // FIXME: try to get the return value for process
-    //        loop until the process is finished
-    loop{    
-        let ret = syscall!(
-            Syscall::WaitPid,
-            pid
-        ) as isize;
-        if ret != -1{
-            return ret;
-        }
+    sys_waitpid(Some(pid), WaitFlags::NONE).map_or(-1, |(_, ret)| ret)
+}
+
+/// Wait for the child `pid`, or any child if `None`, to exit,
+/// returning its pid and exit code
+///
+/// With `WaitFlags::NOHANG` the pid is 0 if none has exited yet,
+/// `None` if there is no such child.
+#[inline(always)]
+pub fn sys_waitpid(pid: Option<u16>, flags: WaitFlags) -> Option<(u16, isize)> {
+    let mut status = 0isize;
+    let ret = syscall!(
+        Syscall::WaitPid,
+        pid.map_or(-1, |pid| pid as isize),
+        flags.0,
+        &mut status as *mut isize
+    ) as isize;
+    if ret.is_negative() {
+        None
+    } else {
+        Some((ret as u16, status))
     }
 }
 
@@ -75,6 +238,38 @@ pub fn sys_get_pid() -> u16 {
     syscall!(Syscall::GetPid) as u16
 }
 
+/// Send `sig` to the process `pid`
+#[inline(always)]
+pub fn sys_kill(pid: u16, sig: Signal) -> bool {
+    syscall!(Syscall::Kill, pid as u64, sig as u64) == 0
+}
+
+/// Move `pid` into the group `pgid`, 0 stands for the caller and `pid`
+#[inline(always)]
+pub fn sys_setpgid(pid: u16, pgid: u16) -> bool {
+    syscall!(Syscall::SetPgid, pid as u64, pgid as u64) == 0
+}
+
+/// Let the console signal the group `pgid` on Ctrl-C, 0 for none,
+/// which must be in the session of the caller
+#[inline(always)]
+pub fn sys_set_foreground(pgid: u16) -> bool {
+    syscall!(Syscall::SetForeground, pgid as u64) == 0
+}
+
+/// Set the handler of `sig`, `SIG_DFL`, `SIG_IGN` or a function
+/// returning to `restorer`, see `signal::signal`
+#[inline(always)]
+pub fn sys_sigaction(sig: Signal, handler: usize, restorer: usize) -> bool {
+    syscall!(Syscall::SigAction, sig as u64, handler as u64, restorer as u64) == 0
+}
+
+/// Change the blocked signals, returning the old mask
+#[inline(always)]
+pub fn sys_sigprocmask(how: SigHow, set: u64) -> u64 {
+    syscall!(Syscall::SigProcMask, how as u64, set) as u64
+}
+
 #[inline(always)]
 pub fn sys_exit(code: isize) -> ! {
     syscall!(Syscall::Exit, code as u64);
@@ -86,14 +281,29 @@ pub fn sys_time() -> u64 {
     syscall!(Syscall::Time) as u64
 }
 
+/// Returns 0 in the child and the pid of the child in the parent,
+/// `u16::MAX` if no process could be made
 #[inline(always)]
 pub fn sys_fork() -> u16 {
     syscall!(Syscall::Fork) as u16
 }
 
+/// Create a semaphore, failing if one of `key` is still in use
+#[inline(always)]
+pub fn sys_new_sem(key: u32, value: usize, flags: SemFlags) -> bool {
+    syscall!(Syscall::Sem, 0, key as usize, value, flags.0) == 0
+}
+
+/// Open a semaphore created by another process
+#[inline(always)]
+pub fn sys_open_sem(key: u32) -> bool {
+    syscall!(Syscall::Sem, 4, key as usize) == 0
+}
+
+/// Let go of a semaphore, it is dropped after every process does
 #[inline(always)]
-pub fn sys_new_sem(key: u32, value: usize) -> bool {
-    syscall!(Syscall::Sem, 0, key as usize, value) == 0
+pub fn sys_close_sem(key: u32) -> bool {
+    syscall!(Syscall::Sem, 5, key as usize) == 0
 }
 
 #[inline(always)]
@@ -106,11 +316,138 @@ pub fn sys_wait(key: u32) -> bool {
     syscall!(Syscall::Sem, 3, key as usize) == 0
 }
 
+/// Wait the semaphore only if it does not block
+#[inline(always)]
+pub fn sys_try_wait(key: u32) -> Result<(), SemError> {
+    match syscall!(Syscall::Sem, 6, key as usize) {
+        0 => Ok(()),
+        ret => Err(SemError::try_from(ret).unwrap_or(SemError::NotExist)),
+    }
+}
+
+/// Wait the semaphore for `ms` milliseconds at most
+#[inline(always)]
+pub fn sys_timed_wait(key: u32, ms: u64) -> Result<(), SemError> {
+    match syscall!(Syscall::Sem, 7, key as usize, ms as usize) {
+        0 => Ok(()),
+        ret => Err(SemError::try_from(ret).unwrap_or(SemError::NotExist)),
+    }
+}
+
+/// Remove a semaphore created by this process, waking up its waiters to fail
 #[inline(always)]
 pub fn sys_remove(key: u32) -> bool {
     syscall!(Syscall::Sem, 1, key as usize) == 0
 }
 
+/// Create a shared memory segment of at least `size` bytes
+#[inline(always)]
+pub fn sys_shm_create(key: u32, size: usize) -> bool {
+    syscall!(Syscall::Shm, 0, key as usize, size) == 0
+}
+
+/// Remove the segment, it lives on until every process detaches it
+#[inline(always)]
+pub fn sys_shm_remove(key: u32) -> bool {
+    syscall!(Syscall::Shm, 1, key as usize) == 0
+}
+
+/// Map the segment into this process, returning its address
+#[inline(always)]
+pub fn sys_shm_attach(key: u32) -> Option<*mut u8> {
+    let ptr = syscall!(Syscall::Shm, 2, key as usize) as *mut u8;
+    (!ptr.is_null()).then_some(ptr)
+}
+
+#[inline(always)]
+pub fn sys_shm_detach(key: u32) -> bool {
+    syscall!(Syscall::Shm, 3, key as usize) == 0
+}
+
+/// Open the message queue of `key`, `MsgFlags::CREATE` creates it if missing
+///
+/// Unlike `msgget`, no queue id is returned: queues are named by
+/// their key in every other call, so this only reports success.
+#[inline(always)]
+pub fn sys_msgget(key: u32, flags: MsgFlags) -> bool {
+    syscall!(Syscall::MsgGet, key as usize, flags.0) == 0
+}
+
+/// Remove the message queue, processes waiting on it fail
+#[inline(always)]
+pub fn sys_msg_remove(key: u32) -> bool {
+    syscall!(Syscall::MsgCtl, key as usize, MsgCmd::Remove as usize) == 0
+}
+
+/// Send `text` as a message of `ty`, which must be positive
+#[inline(always)]
+pub fn sys_msgsnd(key: u32, ty: isize, text: &[u8], flags: MsgFlags) -> bool {
+    let mut msg = Vec::with_capacity(core::mem::size_of::<isize>() + text.len());
+    msg.extend_from_slice(&ty.to_ne_bytes());
+    msg.extend_from_slice(text);
+
+    syscall!(
+        Syscall::MsgSnd,
+        key as usize,
+        msg.as_ptr() as usize,
+        text.len(),
+        flags.0
+    ) == 0
+}
+
+/// Receive a message into `buf`, returning its type and length
+///
+/// `ty` 0 takes the first message, a positive `ty` the first one of that
+/// type, and a negative `ty` the first one of the lowest type up to `-ty`.
+/// Longer messages are truncated to fit `buf`.
+#[inline(always)]
+pub fn sys_msgrcv(key: u32, ty: isize, buf: &mut [u8], flags: MsgFlags) -> Option<(isize, usize)> {
+    const HEADER: usize = core::mem::size_of::<isize>();
+    let mut msg = vec![0u8; HEADER + buf.len()];
+
+    let ret = syscall!(
+        Syscall::MsgRcv,
+        key as usize,
+        msg.as_mut_ptr() as usize,
+        buf.len(),
+        ty as usize,
+        flags.0
+    ) as isize;
+    if ret.is_negative() {
+        return None;
+    }
+
+    let len = ret as usize;
+    buf[..len].copy_from_slice(&msg[HEADER..HEADER + len]);
+    let ty = isize::from_ne_bytes(msg[..HEADER].try_into().unwrap());
+    Some((ty, len))
+}
+
+/// Block while `word` holds `expected`, until `sys_futex_wake` on it
+///
+/// Returns `false` at once if the value is different.
+#[inline(always)]
+pub fn sys_futex_wait(word: &AtomicU32, expected: u32) -> bool {
+    syscall!(
+        Syscall::Futex,
+        word.as_ptr() as usize,
+        FutexOp::Wait as usize,
+        expected as usize
+    ) == 0
+}
+
+/// Wake up `count` processes waiting on `word` at most, returning how many
+#[inline(always)]
+pub fn sys_futex_wake(word: &AtomicU32, count: usize) -> usize {
+    let ret = syscall!(
+        Syscall::Futex,
+        word.as_ptr() as usize,
+        FutexOp::Wake as usize,
+        count
+    ) as isize;
+    ret.max(0) as usize
+}
+
 #[inline(always)]
 pub fn sys_list_dir(path: &str) {
     syscall!(Syscall::ListDir, path.as_ptr() as u64, path.len() as u64);
@@ -119,4 +456,20 @@ pub fn sys_list_dir(path: &str) {
 #[inline(always)]
 pub fn sys_cat(path: &str) {
     syscall!(Syscall::Cat, path.as_ptr() as u64, path.len() as u64);
-}
\ No newline at end of file
+}
+
+#[inline(always)]
+pub fn sys_mount(source: &str, target: &str) -> bool {
+    syscall!(
+        Syscall::Mount,
+        source.as_ptr() as u64,
+        source.len() as u64,
+        target.as_ptr() as u64,
+        target.len() as u64
+    ) == 0
+}
+
+#[inline(always)]
+pub fn sys_umount(target: &str) -> bool {
+    syscall!(Syscall::Umount, target.as_ptr() as u64, target.len() as u64) == 0
+}
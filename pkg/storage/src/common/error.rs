@@ -16,6 +16,10 @@ pub enum FsError {
     NotADirectory,
     /// The entry is not a file.
     NotAFile,
+    /// The entry already exists.
+    AlreadyExists,
+    /// The directory is not empty.
+    DirectoryNotEmpty,
     /// The file is read-only.
     ReadOnly,
     /// Invalid operation.
@@ -32,6 +36,8 @@ pub enum FsError {
     DeviceError(DeviceError),
     /// Invalid path.
     InvalidPath(String),
+    /// The partition table is missing or corrupted.
+    InvalidPartitionTable,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -18,26 +18,37 @@ pub trait FileSystem: Debug + Sync + Send {
     fn exists(&self, path: &str) -> Result<bool>;
 
     // ----------------------------------------------------
-    // NOTE: following functions are not implemented (optional)
+    // NOTE: following functions are optional, see `TmpFs`
+    //       for a reference implementation
     // ----------------------------------------------------
 
-    /// Creates a file at this path for writing
+    /// Creates a file at this path for writing, truncates it if it exists
     fn create_file(&self, _path: &str) -> Result<FileHandle> {
         Err(FsError::NotSupported)
     }
 
-    /// Opens the file at this path for appending
+    /// Opens the file at this path for appending, creates it if it does not exist
     fn append_file(&self, _path: &str) -> Result<FileHandle> {
         Err(FsError::NotSupported)
     }
 
+    /// Creates an empty directory at this path
+    fn create_dir(&self, _path: &str) -> Result<()> {
+        Err(FsError::NotSupported)
+    }
+
+    /// Creates a named pipe at this path, removed with `remove_file`
+    fn create_fifo(&self, _path: &str) -> Result<()> {
+        Err(FsError::NotSupported)
+    }
+
     /// Removes the file at this path
-    fn remove_file(&self, _path: &str) -> Result<FileHandle> {
+    fn remove_file(&self, _path: &str) -> Result<()> {
         Err(FsError::NotSupported)
     }
 
-    /// Removes the directory at this path
-    fn remove_dir(&self, _path: &str) -> Result<FileHandle> {
+    /// Removes the empty directory at this path
+    fn remove_dir(&self, _path: &str) -> Result<()> {
         Err(FsError::NotSupported)
     }
 
@@ -45,9 +45,16 @@ pub trait Write {
     fn flush(&mut self) -> Result<()>;
 
     /// Attempts to write an entire buffer into this writer.
-    fn write_all(&mut self, mut _buf: &[u8]) -> Result<()> {
-        // not required for lab
-        todo!()
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.write(buf) {
+                Ok(0) => return Err(FsError::WriteZero),
+                Ok(written) => buf = &buf[written..],
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -70,6 +77,35 @@ pub trait Seek {
     fn seek(&mut self, pos: SeekFrom) -> Result<usize>;
 }
 
+/// Resolve `pos` against the `current` offset and the `len` of a stream
+///
+/// Offsets past the end are allowed, offsets before the start
+/// or beyond `usize` are `FsError::InvalidOffset`.
+pub fn seek_offset(pos: SeekFrom, current: usize, len: usize) -> Result<usize> {
+    let offset = match pos {
+        SeekFrom::Start(offset) => Some(offset),
+        SeekFrom::Current(delta) => current.checked_add_signed(delta),
+        SeekFrom::End(delta) => len.checked_add_signed(delta),
+    };
+
+    offset.ok_or(FsError::InvalidOffset)
+}
+
 pub trait FileIO: Read + Write + Seek {}
 
 impl<T: Read + Write + Seek> FileIO for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_offset() {
+        assert_eq!(seek_offset(SeekFrom::Start(42), 10, 20), Ok(42));
+        assert_eq!(seek_offset(SeekFrom::Current(-4), 10, 20), Ok(6));
+        assert_eq!(seek_offset(SeekFrom::End(5), 10, 20), Ok(25));
+
+        assert_eq!(seek_offset(SeekFrom::Current(-11), 10, 20), Err(FsError::InvalidOffset));
+        assert_eq!(seek_offset(SeekFrom::End(-21), 10, 20), Err(FsError::InvalidOffset));
+    }
+}
@@ -53,6 +53,15 @@ macro_rules! define_field {
         }
     };
 
+    (u64, $offset:expr, $name:ident) => {
+        paste::item! {
+            #[doc = "Get u64 from the " $name " field"]
+            pub fn $name(&self) -> u64 {
+                u64::from_le_bytes(self.data[$offset..$offset + 8].try_into().unwrap_or([0; 8]))
+            }
+        }
+    };
+
     ([u8; $len:expr], $offset:expr, $name:ident) => {
         paste::item! {
             #[doc = "Get `&[u8]` from the " $name " field"]
@@ -10,6 +10,33 @@ pub enum FileType {
     File,
     /// A Directory
     Directory,
+    /// A symbolic link
+    Symlink,
+    /// A named pipe, its data only lives in the kernel
+    Fifo,
+}
+
+/// POSIX attributes of a file entry
+///
+/// Only available on filesystems that store them, e.g. ext2
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PosixMetadata {
+    /// File type and permission bits, as in `st_mode`
+    pub mode: u16,
+    /// Owner user id
+    pub uid: u32,
+    /// Owner group id
+    pub gid: u32,
+    /// Count of hard links
+    pub links: u16,
+}
+
+impl PosixMetadata {
+    /// The permission bits of the mode, e.g. `0o755`
+    #[inline]
+    pub fn permissions(&self) -> u16 {
+        self.mode & 0o7777
+    }
 }
 
 #[derive(Debug)]
@@ -27,6 +54,8 @@ pub struct Metadata {
     pub modified: Option<FsTime>,
     /// Access time of the file
     pub accessed: Option<FsTime>,
+    /// POSIX attributes of the file, if the filesystem has them
+    pub posix: Option<PosixMetadata>,
 }
 
 impl Metadata {
@@ -46,9 +75,16 @@ impl Metadata {
             modified,
             accessed,
             entry_type,
+            posix: None,
         }
     }
 
+    /// Attach POSIX attributes to the metadata
+    pub fn with_posix(mut self, posix: PosixMetadata) -> Self {
+        self.posix = Some(posix);
+        self
+    }
+
     /// Return `true` if the entry is a file
     #[inline]
     pub fn is_file(&self) -> bool {
@@ -60,4 +96,16 @@ impl Metadata {
     pub fn is_dir(&self) -> bool {
         self.entry_type == FileType::Directory
     }
+
+    /// Return `true` if the entry is a symbolic link
+    #[inline]
+    pub fn is_symlink(&self) -> bool {
+        self.entry_type == FileType::Symlink
+    }
+
+    /// Return `true` if the entry is a named pipe
+    #[inline]
+    pub fn is_fifo(&self) -> bool {
+        self.entry_type == FileType::Fifo
+    }
 }
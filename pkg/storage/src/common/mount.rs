@@ -1,8 +1,12 @@
 use super::*;
+use alloc::collections::BTreeMap;
+use alloc::string::ToString;
+use spin::RwLock;
 
 /// Mount a file system to a specific path
 ///
-/// NOTE: strip the mount point from the path before calling the underlying file system
+/// NOTE: strip the mount point from the path before calling the underlying file system,
+///       the underlying file system receives a path relative to its root, e.g. `app/sh`
 pub struct Mount {
     pub fs: Box<dyn FileSystem>,
     pub mount_point: Box<str>,
@@ -14,9 +18,21 @@ impl Mount {
         Self { fs, mount_point }
     }
 
+    /// Check if the mount point is the path or one of its ancestors
+    pub fn contains(&self, path: &str) -> bool {
+        let mount_point = self.mount_point.trim_end_matches(PATH_SEPARATOR);
+        match path.strip_prefix(mount_point) {
+            Some(rest) => rest.is_empty() || rest.starts_with(PATH_SEPARATOR),
+            None => false,
+        }
+    }
+
     #[inline]
     fn trim_mount_point<'a>(&self, path: &'a str) -> &'a str {
-        path.trim_start_matches(self.mount_point.as_ref())
+        let mount_point = self.mount_point.trim_end_matches(PATH_SEPARATOR);
+        path.strip_prefix(mount_point)
+            .unwrap_or(path)
+            .trim_start_matches(PATH_SEPARATOR)
     }
 }
 
@@ -40,6 +56,54 @@ impl FileSystem for Mount {
     fn exists(&self, path: &str) -> Result<bool> {
         self.fs.exists(self.trim_mount_point(path))
     }
+
+    #[inline]
+    fn create_file(&self, path: &str) -> Result<FileHandle> {
+        self.fs.create_file(self.trim_mount_point(path))
+    }
+
+    #[inline]
+    fn append_file(&self, path: &str) -> Result<FileHandle> {
+        self.fs.append_file(self.trim_mount_point(path))
+    }
+
+    #[inline]
+    fn create_dir(&self, path: &str) -> Result<()> {
+        self.fs.create_dir(self.trim_mount_point(path))
+    }
+
+    #[inline]
+    fn create_fifo(&self, path: &str) -> Result<()> {
+        self.fs.create_fifo(self.trim_mount_point(path))
+    }
+
+    #[inline]
+    fn remove_file(&self, path: &str) -> Result<()> {
+        self.fs.remove_file(self.trim_mount_point(path))
+    }
+
+    #[inline]
+    fn remove_dir(&self, path: &str) -> Result<()> {
+        self.fs.remove_dir(self.trim_mount_point(path))
+    }
+
+    #[inline]
+    fn copy_file(&self, src: &str, dst: &str) -> Result<()> {
+        self.fs
+            .copy_file(self.trim_mount_point(src), self.trim_mount_point(dst))
+    }
+
+    #[inline]
+    fn move_file(&self, src: &str, dst: &str) -> Result<()> {
+        self.fs
+            .move_file(self.trim_mount_point(src), self.trim_mount_point(dst))
+    }
+
+    #[inline]
+    fn move_dir(&self, src: &str, dst: &str) -> Result<()> {
+        self.fs
+            .move_dir(self.trim_mount_point(src), self.trim_mount_point(dst))
+    }
 }
 
 impl core::fmt::Debug for Mount {
@@ -50,3 +114,312 @@ impl core::fmt::Debug for Mount {
             .finish()
     }
 }
+
+/// Normalize an absolute path, `.` and `..` are resolved lexically
+///
+/// e.g. `/tmp/../app//sh` -> `/app/sh`
+pub fn normalize_path(path: &str) -> String {
+    let mut parts = Vec::new();
+
+    for part in path.split(PATH_SEPARATOR) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+
+    let mut normalized = String::from("/");
+    normalized.push_str(&parts.join("/"));
+    normalized
+}
+
+/// The table of all mounted file systems
+///
+/// Paths are resolved to the mount with the longest mount point,
+/// so a file system mounted at `/tmp` hides `/tmp` of the root file system.
+pub struct MountTable {
+    mounts: RwLock<BTreeMap<String, Arc<Mount>>>,
+}
+
+impl MountTable {
+    pub const fn new() -> Self {
+        Self {
+            mounts: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Mount a file system at the path, the path is not required to exist
+    pub fn mount(&self, fs: Box<dyn FileSystem>, mount_point: &str) -> Result<()> {
+        let mount_point = normalize_path(mount_point);
+        let mut mounts = self.mounts.write();
+
+        if mounts.contains_key(&mount_point) {
+            return Err(FsError::AlreadyExists);
+        }
+
+        trace!("Mounting {:?} at {}", fs, mount_point);
+
+        let mount = Mount::new(fs, mount_point.as_str().into());
+        mounts.insert(mount_point, Arc::new(mount));
+        Ok(())
+    }
+
+    /// Unmount the file system at the path
+    ///
+    /// Files that are still open keep working until they are closed,
+    /// a mount point that has other mounts below can not be unmounted.
+    pub fn umount(&self, mount_point: &str) -> Result<()> {
+        let mount_point = normalize_path(mount_point);
+        let mut mounts = self.mounts.write();
+
+        let mount = mounts.get(&mount_point).ok_or(FsError::FileNotFound)?;
+
+        if mounts
+            .keys()
+            .any(|other| *other != mount_point && mount.contains(other))
+        {
+            return Err(FsError::InvalidOperation);
+        }
+
+        mounts.remove(&mount_point);
+        Ok(())
+    }
+
+    /// Find the mount that holds the path
+    pub fn resolve(&self, path: &str) -> Result<Arc<Mount>> {
+        let path = normalize_path(path);
+        self.mounts
+            .read()
+            .values()
+            .filter(|mount| mount.contains(&path))
+            .max_by_key(|mount| mount.mount_point.len())
+            .cloned()
+            .ok_or(FsError::FileNotFound)
+    }
+
+    /// List all mounts, ordered by the mount point
+    pub fn mounts(&self) -> Vec<Arc<Mount>> {
+        self.mounts.read().values().cloned().collect()
+    }
+
+    /// Names of the mount points directly below the directory
+    ///
+    /// `/mnt/hda2` shows up as `mnt` in `/`, even if `/mnt` does not exist.
+    fn child_mount_points(&self, dir: &str) -> Vec<String> {
+        let prefix = dir.trim_end_matches(PATH_SEPARATOR);
+        let mut children: Vec<String> = self
+            .mounts
+            .read()
+            .keys()
+            .filter_map(|mount_point| mount_point.strip_prefix(prefix))
+            .filter_map(|rest| rest.strip_prefix(PATH_SEPARATOR))
+            .filter_map(|rest| rest.split(PATH_SEPARATOR).next())
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+            .collect();
+        children.sort();
+        children.dedup();
+        children
+    }
+
+    /// Resolve both paths to their mounts, which may differ
+    ///
+    /// The caller decides what a pair across mounts means,
+    /// compare the mounts with `Arc::ptr_eq`.
+    fn resolve_pair(&self, src: &str, dst: &str) -> Result<(Arc<Mount>, Arc<Mount>, String, String)> {
+        let (src, dst) = (normalize_path(src), normalize_path(dst));
+        Ok((self.resolve(&src)?, self.resolve(&dst)?, src, dst))
+    }
+}
+
+/// Metadata of a mount point or a directory that only holds mount points
+fn dir_meta(path: &str) -> Metadata {
+    let name = path.rsplit(PATH_SEPARATOR).next().unwrap_or_default();
+    Metadata::new(name.into(), FileType::Directory, 0, None, None, None)
+}
+
+impl Default for MountTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem for MountTable {
+    fn read_dir(&self, path: &str) -> Result<Box<dyn Iterator<Item = Metadata> + Send>> {
+        let path = normalize_path(path);
+        let children = self.child_mount_points(&path);
+
+        let entries = match self.resolve(&path).and_then(|mount| mount.read_dir(&path)) {
+            Ok(entries) => entries,
+            // the directory only holds mount points
+            Err(FsError::FileNotFound) if !children.is_empty() => Box::new(core::iter::empty()),
+            Err(e) => return Err(e),
+        };
+
+        if children.is_empty() {
+            return Ok(entries);
+        }
+
+        let mut entries: Vec<Metadata> = entries
+            .filter(|meta| !children.contains(&meta.name))
+            .collect();
+
+        for name in children {
+            entries.push(Metadata::new(name, FileType::Directory, 0, None, None, None));
+        }
+
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn open_file(&self, path: &str) -> Result<FileHandle> {
+        let path = normalize_path(path);
+        self.resolve(&path)?.open_file(&path)
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata> {
+        let path = normalize_path(path);
+        let mount = self.resolve(&path)?;
+
+        if mount.mount_point.as_ref() == path {
+            return Ok(dir_meta(&path));
+        }
+
+        match mount.metadata(&path) {
+            Err(FsError::FileNotFound) if !self.child_mount_points(&path).is_empty() => {
+                Ok(dir_meta(&path))
+            }
+            other => other,
+        }
+    }
+
+    fn exists(&self, path: &str) -> Result<bool> {
+        let path = normalize_path(path);
+        let mount = self.resolve(&path)?;
+
+        if mount.mount_point.as_ref() == path || !self.child_mount_points(&path).is_empty() {
+            return Ok(true);
+        }
+
+        mount.exists(&path)
+    }
+
+    fn create_file(&self, path: &str) -> Result<FileHandle> {
+        let path = normalize_path(path);
+        self.resolve(&path)?.create_file(&path)
+    }
+
+    fn append_file(&self, path: &str) -> Result<FileHandle> {
+        let path = normalize_path(path);
+        self.resolve(&path)?.append_file(&path)
+    }
+
+    fn create_dir(&self, path: &str) -> Result<()> {
+        let path = normalize_path(path);
+        self.resolve(&path)?.create_dir(&path)
+    }
+
+    fn create_fifo(&self, path: &str) -> Result<()> {
+        let path = normalize_path(path);
+        self.resolve(&path)?.create_fifo(&path)
+    }
+
+    fn remove_file(&self, path: &str) -> Result<()> {
+        let path = normalize_path(path);
+        self.resolve(&path)?.remove_file(&path)
+    }
+
+    fn remove_dir(&self, path: &str) -> Result<()> {
+        let path = normalize_path(path);
+        let mount = self.resolve(&path)?;
+
+        // mount points are removed by `umount`
+        if mount.mount_point.as_ref() == path || !self.child_mount_points(&path).is_empty() {
+            return Err(FsError::InvalidOperation);
+        }
+
+        mount.remove_dir(&path)
+    }
+
+    fn copy_file(&self, src: &str, dst: &str) -> Result<()> {
+        let (src_mount, dst_mount, src, dst) = self.resolve_pair(src, dst)?;
+
+        if Arc::ptr_eq(&src_mount, &dst_mount) {
+            return src_mount.copy_file(&src, &dst);
+        }
+
+        let mut buf = Vec::new();
+        src_mount.open_file(&src)?.read_all(&mut buf)?;
+        dst_mount.create_file(&dst)?.write_all(&buf)
+    }
+
+    fn move_file(&self, src: &str, dst: &str) -> Result<()> {
+        let (src_mount, dst_mount, src, dst) = self.resolve_pair(src, dst)?;
+
+        if Arc::ptr_eq(&src_mount, &dst_mount) {
+            return src_mount.move_file(&src, &dst);
+        }
+
+        self.copy_file(&src, &dst)?;
+        src_mount.remove_file(&src)
+    }
+
+    fn move_dir(&self, src: &str, dst: &str) -> Result<()> {
+        let (src_mount, dst_mount, src, dst) = self.resolve_pair(src, dst)?;
+
+        if !Arc::ptr_eq(&src_mount, &dst_mount) || src_mount.mount_point.as_ref() == src {
+            return Err(FsError::InvalidOperation);
+        }
+
+        src_mount.move_dir(&src, &dst)
+    }
+}
+
+impl core::fmt::Debug for MountTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.mounts.read().values()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmpfs::TmpFs;
+
+    #[test]
+    fn test_mount_table() {
+        assert_eq!(normalize_path("/tmp/../app//./sh/"), "/app/sh");
+        assert_eq!(normalize_path(".."), "/");
+
+        let table = MountTable::new();
+        table.mount(Box::new(TmpFs::new()), "/").unwrap();
+        table.mount(Box::new(TmpFs::new()), "/tmp").unwrap();
+        table.mount(Box::new(TmpFs::new()), "/mnt/hda2").unwrap();
+        assert_eq!(
+            table.mount(Box::new(TmpFs::new()), "/tmp/"),
+            Err(FsError::AlreadyExists)
+        );
+
+        assert_eq!(table.resolve("/tmpfile").unwrap().mount_point.as_ref(), "/");
+        assert_eq!(table.resolve("/tmp/a").unwrap().mount_point.as_ref(), "/tmp");
+        assert_eq!(table.resolve("/tmp/../a").unwrap().mount_point.as_ref(), "/");
+
+        table.create_file("/tmp/a").unwrap().write_all(b"a").unwrap();
+        assert!(table.exists("/tmp/a").unwrap());
+        assert!(!table.exists("/a").unwrap());
+        assert!(table.metadata("/mnt").unwrap().is_dir());
+
+        let names: Vec<String> = table.read_dir("/").unwrap().map(|m| m.name).collect();
+        assert_eq!(names, ["mnt", "tmp"]);
+
+        table.move_file("/tmp/a", "/mnt/hda2/b").unwrap();
+        assert!(!table.exists("/tmp/a").unwrap());
+        assert_eq!(table.metadata("/mnt/hda2/b").unwrap().len, 1);
+
+        assert_eq!(table.umount("/"), Err(FsError::InvalidOperation));
+        table.umount("/tmp").unwrap();
+        assert!(table.metadata("/tmp").is_err());
+    }
+}
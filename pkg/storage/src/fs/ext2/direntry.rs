@@ -0,0 +1,70 @@
+//! Ext2 Directory Entry
+//!
+//! reference: <https://www.nongnu.org/ext2-doc/ext2.html#linked-directories>
+
+use crate::*;
+
+/// Represents an entry of a linked directory.
+///
+/// Entries are chained by `rec_len`, an entry never spans two blocks.
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    /// Inode number of the entry, 0 if the entry is unused
+    pub inode: u32,
+    /// Displacement to the next entry
+    pub rec_len: u16,
+    /// File type hint, only valid with the `filetype` feature
+    pub file_type: u8,
+    pub name: String,
+}
+
+impl DirEntry {
+    /// Size of the fixed part of an entry
+    pub const HEADER_LEN: usize = 8;
+
+    pub const FT_UNKNOWN: u8 = 0;
+    pub const FT_REG_FILE: u8 = 1;
+    pub const FT_DIR: u8 = 2;
+    pub const FT_CHRDEV: u8 = 3;
+    pub const FT_BLKDEV: u8 = 4;
+    pub const FT_FIFO: u8 = 5;
+    pub const FT_SOCK: u8 = 6;
+    pub const FT_SYMLINK: u8 = 7;
+
+    /// Parse the entry at the beginning of `data`.
+    ///
+    /// Revision 0 volumes use the whole 16 bits as the name length.
+    pub fn parse(data: &[u8], has_filetype: bool) -> Result<DirEntry> {
+        if data.len() < Self::HEADER_LEN {
+            return Err(FsError::InvalidOffset);
+        }
+
+        let inode = u32::from_le_bytes(data[0..4].try_into().unwrap());
+        let rec_len = u16::from_le_bytes([data[4], data[5]]);
+        let (name_len, file_type) = if has_filetype {
+            (data[6] as usize, data[7])
+        } else {
+            (u16::from_le_bytes([data[6], data[7]]) as usize, Self::FT_UNKNOWN)
+        };
+
+        if (rec_len as usize) < Self::HEADER_LEN
+            || rec_len as usize > data.len()
+            || Self::HEADER_LEN + name_len > rec_len as usize
+        {
+            return Err(FsError::InvalidOffset);
+        }
+
+        let name = &data[Self::HEADER_LEN..Self::HEADER_LEN + name_len];
+
+        Ok(DirEntry {
+            inode,
+            rec_len,
+            file_type,
+            name: String::from_utf8_lossy(name).into_owned(),
+        })
+    }
+
+    pub fn is_unused(&self) -> bool {
+        self.inode == 0
+    }
+}
@@ -0,0 +1,54 @@
+//! File
+//!
+//! reference: <https://www.nongnu.org/ext2-doc/ext2.html#i-block>
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The current offset in the file
+    offset: usize,
+    /// Inode of this file
+    inode: Inode,
+    /// The file system handle that contains this file
+    handle: Ext2Handle,
+}
+
+impl File {
+    pub fn new(handle: Ext2Handle, inode: Inode) -> Self {
+        Self {
+            offset: 0,
+            inode,
+            handle,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.inode.size()
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.handle.read_inode_data(&self.inode, self.offset, buf)?;
+        self.offset += read;
+        Ok(read)
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize> {
+        self.offset = seek_offset(pos, self.offset, self.length())?;
+        Ok(self.offset)
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+        Err(FsError::ReadOnly)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Err(FsError::ReadOnly)
+    }
+}
@@ -0,0 +1,45 @@
+//! Ext2 Block Group Descriptor
+//!
+//! reference: <https://www.nongnu.org/ext2-doc/ext2.html#block-group-descriptor-table>
+
+/// Represents a block group descriptor.
+///
+/// The descriptor table starts at the block following the superblock,
+/// each descriptor locates the bitmaps and the inode table of its group.
+pub struct BlockGroupDescriptor {
+    data: [u8; 32],
+}
+
+impl BlockGroupDescriptor {
+    pub const LEN: usize = 32;
+
+    /// Parse a block group descriptor from its 32 bytes.
+    pub fn new(data: &[u8]) -> Result<BlockGroupDescriptor, &'static str> {
+        let data = data
+            .get(..Self::LEN)
+            .and_then(|data| data.try_into().ok())
+            .ok_or("Bad group descriptor size")?;
+
+        Ok(BlockGroupDescriptor { data })
+    }
+
+    define_field!(u32, 0x00, block_bitmap);
+    define_field!(u32, 0x04, inode_bitmap);
+    define_field!(u32, 0x08, inode_table);
+    define_field!(u16, 0x0C, free_blocks_count);
+    define_field!(u16, 0x0E, free_inodes_count);
+    define_field!(u16, 0x10, used_dirs_count);
+}
+
+impl core::fmt::Debug for BlockGroupDescriptor {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Block Group Descriptor")
+            .field("Block Bitmap", &self.block_bitmap())
+            .field("Inode Bitmap", &self.inode_bitmap())
+            .field("Inode Table", &self.inode_table())
+            .field("Free Blocks Count", &self.free_blocks_count())
+            .field("Free Inodes Count", &self.free_inodes_count())
+            .field("Used Dirs Count", &self.used_dirs_count())
+            .finish()
+    }
+}
@@ -0,0 +1,304 @@
+use super::*;
+
+impl Ext2Impl {
+    pub fn new(inner: impl BlockDevice<Block512>) -> Self {
+        let mut data = [0u8; Ext2SuperBlock::SIZE];
+        Self::read_bytes(&inner, Ext2SuperBlock::OFFSET, &mut data).unwrap();
+        let sb = Ext2SuperBlock::new(&data).unwrap();
+
+        trace!("Loading Ext2 Volume: {:#?}", sb);
+
+        let block_size = sb.block_size();
+
+        // the descriptor table follows the block of the superblock
+        let table_offset = (sb.first_data_block() as usize + 1) * block_size;
+        let mut table = vec![0u8; sb.group_count() * BlockGroupDescriptor::LEN];
+        Self::read_bytes(&inner, table_offset, &mut table).unwrap();
+
+        let groups = table
+            .chunks(BlockGroupDescriptor::LEN)
+            .map(|desc| BlockGroupDescriptor::new(desc).unwrap())
+            .collect();
+
+        Self {
+            inner: Box::new(inner),
+            sb,
+            groups,
+            block_size,
+        }
+    }
+
+    /// Read bytes at an arbitrary offset of the device
+    pub(crate) fn read_bytes<D>(inner: &D, offset: usize, buf: &mut [u8]) -> Result<()>
+    where
+        D: BlockDevice<Block512> + ?Sized,
+    {
+        let mut block = Block::default();
+        let mut done = 0;
+
+        while done < buf.len() {
+            let pos = offset + done;
+            let in_block = pos % BLOCK_SIZE;
+            let len = (BLOCK_SIZE - in_block).min(buf.len() - done);
+
+            inner.read_block(pos / BLOCK_SIZE, &mut block)?;
+            buf[done..done + len].copy_from_slice(&block[in_block..in_block + len]);
+
+            done += len;
+        }
+
+        Ok(())
+    }
+
+    /// Read `buf.len()` bytes from the ext2 block at `offset` within the block
+    fn read_block_data(&self, block: u32, offset: usize, buf: &mut [u8]) -> Result<()> {
+        if block == 0 {
+            // sparse block, never allocated
+            buf.fill(0);
+            return Ok(());
+        }
+
+        Self::read_bytes(
+            self.inner.as_ref(),
+            block as usize * self.block_size + offset,
+            buf,
+        )
+    }
+
+    /// Read the `idx`-th pointer of an indirect block
+    fn read_indirect(&self, block: u32, idx: usize) -> Result<u32> {
+        let mut ptr = [0u8; 4];
+        self.read_block_data(block, idx * 4, &mut ptr)?;
+        Ok(u32::from_le_bytes(ptr))
+    }
+
+    pub fn read_inode(&self, ino: u32) -> Result<Inode> {
+        if ino == 0 || ino > self.sb.inodes_count() {
+            return Err(FsError::FileNotFound);
+        }
+
+        let ipg = self.sb.inodes_per_group();
+        let group = ((ino - 1) / ipg) as usize;
+        let index = ((ino - 1) % ipg) as usize;
+
+        let desc = self.groups.get(group).ok_or(FsError::FileNotFound)?;
+
+        let mut data = [0u8; Inode::LEN];
+        self.read_block_data(
+            desc.inode_table(),
+            index * self.sb.inode_size(),
+            &mut data,
+        )?;
+
+        Ok(Inode::new(&data).unwrap())
+    }
+
+    /// Map the `n`-th block of an inode to its block on the volume,
+    /// 0 stands for a hole of a sparse file.
+    pub fn block_of(&self, inode: &Inode, n: usize) -> Result<u32> {
+        let per_block = self.block_size / 4;
+
+        if n < Inode::DIRECT_BLOCKS {
+            return Ok(inode.block(n));
+        }
+
+        let n = n - Inode::DIRECT_BLOCKS;
+        if n < per_block {
+            let ind = inode.block(Inode::INDIRECT_BLOCK);
+            return self.read_indirect(ind, n);
+        }
+
+        let n = n - per_block;
+        if n < per_block * per_block {
+            let dind = inode.block(Inode::DOUBLE_INDIRECT_BLOCK);
+            let ind = self.read_indirect(dind, n / per_block)?;
+            return self.read_indirect(ind, n % per_block);
+        }
+
+        let n = n - per_block * per_block;
+        if n < per_block * per_block * per_block {
+            let tind = inode.block(Inode::TRIPLE_INDIRECT_BLOCK);
+            let dind = self.read_indirect(tind, n / (per_block * per_block))?;
+            let ind = self.read_indirect(dind, n / per_block % per_block)?;
+            return self.read_indirect(ind, n % per_block);
+        }
+
+        Err(FsError::InvalidOffset)
+    }
+
+    /// Read the content of an inode from `offset`, returns the bytes read
+    pub fn read_inode_data(&self, inode: &Inode, offset: usize, buf: &mut [u8]) -> Result<usize> {
+        let size = inode.size();
+        if offset >= size {
+            return Ok(0);
+        }
+
+        let len = buf.len().min(size - offset);
+
+        if inode.is_fast_symlink() {
+            buf[..len].copy_from_slice(&inode.inline_data()[offset..offset + len]);
+            return Ok(len);
+        }
+
+        let mut done = 0;
+        while done < len {
+            let pos = offset + done;
+            let in_block = pos % self.block_size;
+            let to_read = (self.block_size - in_block).min(len - done);
+
+            let block = self.block_of(inode, pos / self.block_size)?;
+            self.read_block_data(block, in_block, &mut buf[done..done + to_read])?;
+
+            done += to_read;
+        }
+
+        Ok(len)
+    }
+
+    pub fn iterate_dir<F>(&self, dir: &Inode, mut func: F) -> Result<()>
+    where
+        F: FnMut(&DirEntry),
+    {
+        if !dir.is_dir() {
+            return Err(FsError::NotADirectory);
+        }
+
+        let mut block = vec![0u8; self.block_size];
+
+        for offset in (0..dir.size()).step_by(self.block_size) {
+            let len = self.read_inode_data(dir, offset, &mut block)?;
+
+            let mut pos = 0;
+            while pos + DirEntry::HEADER_LEN <= len {
+                let entry = match DirEntry::parse(&block[pos..len], self.sb.has_filetype()) {
+                    Ok(entry) => entry,
+                    Err(_) => {
+                        warn!("Corrupted directory entry at block offset {}", pos);
+                        break;
+                    }
+                };
+
+                if !entry.is_unused() {
+                    func(&entry);
+                }
+
+                pos += entry.rec_len as usize;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_entry_by_name(&self, dir: &Inode, name: &str) -> Result<u32> {
+        let mut found = None;
+
+        self.iterate_dir(dir, |entry| {
+            if found.is_none() && entry.name == name {
+                found = Some(entry.inode);
+            }
+        })?;
+
+        found.ok_or(FsError::FileNotFound)
+    }
+
+    fn read_link(&self, inode: &Inode) -> Result<String> {
+        let mut target = vec![0u8; inode.size()];
+        self.read_inode_data(inode, 0, &mut target)?;
+        String::from_utf8(target).map_err(|_| FilenameError::Utf8Error.into())
+    }
+
+    /// Resolve a path to its inode, following symbolic links.
+    ///
+    /// Absolute link targets are resolved from the root of this volume.
+    pub fn find_inode(&self, path: &str) -> Result<Inode> {
+        debug!("Searching for {:?}", path);
+
+        let mut path = path.to_owned();
+        let mut follows = 0;
+
+        'restart: loop {
+            let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+            let mut inode = self.read_inode(ROOT_INODE)?;
+
+            for (idx, part) in parts.iter().enumerate() {
+                if !inode.is_dir() {
+                    return Err(FsError::NotADirectory);
+                }
+
+                let next = self.read_inode(self.find_entry_by_name(&inode, part)?)?;
+
+                if next.is_symlink() {
+                    follows += 1;
+                    if follows > MAX_SYMLINK_FOLLOWS {
+                        return Err(FsError::InvalidPath(path.clone()));
+                    }
+
+                    let target = self.read_link(&next)?;
+                    let base = if target.starts_with('/') {
+                        String::new()
+                    } else {
+                        parts[..idx].join("/")
+                    };
+
+                    path = format!("{}/{}/{}", base, target, parts[idx + 1..].join("/"));
+                    continue 'restart;
+                }
+
+                inode = next;
+            }
+
+            return Ok(inode);
+        }
+    }
+
+    fn read_dir(&self, dir: &Inode) -> Result<Vec<Metadata>> {
+        let mut entries = Vec::new();
+
+        self.iterate_dir(dir, |entry| {
+            entries.push((entry.inode, entry.name.clone()));
+        })?;
+
+        entries
+            .into_iter()
+            .map(|(ino, name)| Ok(self.read_inode(ino)?.as_meta(&name)))
+            .collect()
+    }
+}
+
+impl FileSystem for Ext2 {
+    fn read_dir(&self, path: &str) -> Result<Box<dyn Iterator<Item = Metadata> + Send>> {
+        let dir = self.handle.find_inode(path)?;
+        let entries = self.handle.read_dir(&dir)?;
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn open_file(&self, path: &str) -> Result<FileHandle> {
+        let inode = self.handle.find_inode(path)?;
+        if inode.is_dir() {
+            return Err(FsError::NotAFile);
+        }
+        let meta = inode.as_meta(file_name(path));
+        let file = File::new(self.handle.clone(), inode);
+        Ok(FileHandle::new(meta, Box::new(file)))
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata> {
+        let inode = self.handle.find_inode(path)?;
+        Ok(inode.as_meta(file_name(path)))
+    }
+
+    fn exists(&self, path: &str) -> Result<bool> {
+        match self.handle.find_inode(path) {
+            Ok(_) => Ok(true),
+            Err(FsError::FileNotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The last component of a path, `/` for the root
+fn file_name(path: &str) -> &str {
+    path.rsplit('/')
+        .find(|p| !p.is_empty())
+        .unwrap_or("/")
+}
@@ -0,0 +1,212 @@
+//! Ext2 Inode
+//!
+//! reference: <https://www.nongnu.org/ext2-doc/ext2.html#inode-table>
+
+use crate::{FileType, FsTime, Metadata, PosixMetadata};
+use alloc::borrow::ToOwned;
+use chrono::DateTime;
+
+/// Represents an inode of an ext2 volume.
+///
+/// Only the first 128 bytes are parsed, which are shared by all revisions,
+/// larger inodes only append extra fields.
+#[derive(Clone)]
+pub struct Inode {
+    data: [u8; 128],
+}
+
+impl Inode {
+    pub const LEN: usize = 128;
+
+    /// Count of direct block pointers
+    pub const DIRECT_BLOCKS: usize = 12;
+    /// Index of the singly indirect block pointer
+    pub const INDIRECT_BLOCK: usize = 12;
+    /// Index of the doubly indirect block pointer
+    pub const DOUBLE_INDIRECT_BLOCK: usize = 13;
+    /// Index of the triply indirect block pointer
+    pub const TRIPLE_INDIRECT_BLOCK: usize = 14;
+
+    pub const S_IFMT: u16 = 0xF000;
+    pub const S_IFSOCK: u16 = 0xC000;
+    pub const S_IFLNK: u16 = 0xA000;
+    pub const S_IFREG: u16 = 0x8000;
+    pub const S_IFBLK: u16 = 0x6000;
+    pub const S_IFDIR: u16 = 0x4000;
+    pub const S_IFCHR: u16 = 0x2000;
+    pub const S_IFIFO: u16 = 0x1000;
+
+    /// Parse an inode from the beginning of an inode table slot.
+    pub fn new(data: &[u8]) -> Result<Inode, &'static str> {
+        let data = data
+            .get(..Self::LEN)
+            .and_then(|data| data.try_into().ok())
+            .ok_or("Bad inode size")?;
+
+        Ok(Inode { data })
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.mode() & Self::S_IFMT == Self::S_IFDIR
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.mode() & Self::S_IFMT == Self::S_IFREG
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.mode() & Self::S_IFMT == Self::S_IFLNK
+    }
+
+    /// Devices, FIFOs and sockets are listed as plain files,
+    /// the real type is kept in the mode
+    pub fn file_type(&self) -> FileType {
+        match self.mode() & Self::S_IFMT {
+            Self::S_IFDIR => FileType::Directory,
+            Self::S_IFLNK => FileType::Symlink,
+            Self::S_IFIFO => FileType::Fifo,
+            _ => FileType::File,
+        }
+    }
+
+    /// The size in bytes, the high 32 bits are only used by regular files
+    pub fn size(&self) -> usize {
+        let high = if self.is_file() { self.size_high() } else { 0 };
+        (high as usize) << 32 | self.size_low() as usize
+    }
+
+    /// The `idx`-th entry of `i_block`
+    pub fn block(&self, idx: usize) -> u32 {
+        let offset = 0x28 + idx * 4;
+        u32::from_le_bytes(self.data[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Targets shorter than 60 bytes are stored in `i_block` directly
+    pub fn is_fast_symlink(&self) -> bool {
+        self.is_symlink() && self.size() < 60
+    }
+
+    /// The raw bytes of `i_block`, holding the target of a fast symlink
+    pub fn inline_data(&self) -> &[u8] {
+        &self.data[0x28..0x28 + 60]
+    }
+
+    pub fn uid(&self) -> u32 {
+        (self.uid_high() as u32) << 16 | self.uid_low() as u32
+    }
+
+    pub fn gid(&self) -> u32 {
+        (self.gid_high() as u32) << 16 | self.gid_low() as u32
+    }
+
+    pub fn posix(&self) -> PosixMetadata {
+        PosixMetadata {
+            mode: self.mode(),
+            uid: self.uid(),
+            gid: self.gid(),
+            links: self.links_count(),
+        }
+    }
+
+    pub fn as_meta(&self, name: &str) -> Metadata {
+        Metadata::new(
+            name.to_owned(),
+            self.file_type(),
+            if self.is_dir() { 0 } else { self.size() },
+            parse_time(self.ctime()),
+            parse_time(self.mtime()),
+            parse_time(self.atime()),
+        )
+        .with_posix(self.posix())
+    }
+
+    define_field!(u16, 0x00, mode);
+    define_field!(u16, 0x02, uid_low);
+    define_field!(u32, 0x04, size_low);
+    define_field!(u32, 0x08, atime);
+    define_field!(u32, 0x0C, ctime);
+    define_field!(u32, 0x10, mtime);
+    define_field!(u32, 0x14, dtime);
+    define_field!(u16, 0x18, gid_low);
+    define_field!(u16, 0x1A, links_count);
+    define_field!(u32, 0x1C, sectors);
+    define_field!(u32, 0x20, flags);
+    define_field!(u32, 0x64, generation);
+    define_field!(u32, 0x68, file_acl);
+    define_field!(u32, 0x6C, size_high);
+    define_field!(u16, 0x78, uid_high);
+    define_field!(u16, 0x7A, gid_high);
+}
+
+fn parse_time(secs: u32) -> Option<FsTime> {
+    if secs == 0 {
+        None
+    } else {
+        DateTime::from_timestamp(secs as i64, 0)
+    }
+}
+
+impl core::fmt::Debug for Inode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Inode")
+            .field("Mode", &format_args!("{:o}", self.mode()))
+            .field("UID", &self.uid())
+            .field("GID", &self.gid())
+            .field("Size", &self.size())
+            .field("Links Count", &self.links_count())
+            .field("Sectors", &self.sectors())
+            .field("Flags", &self.flags())
+            .field("Access Time", &parse_time(self.atime()))
+            .field("Change Time", &parse_time(self.ctime()))
+            .field("Modification Time", &parse_time(self.mtime()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inode() {
+        let data = hex_literal::hex!(
+            "ed 41 e8 03 00 04 00 00 0b 89 d5 6a 0b 89 d5 6a
+             0b 89 d5 6a 00 00 00 00 64 00 04 00 02 00 00 00
+             00 00 00 00 00 00 00 00 4c 00 00 00 00 00 00 00"
+        );
+
+        let mut inode_data = [0u8; 256];
+        inode_data[..data.len()].copy_from_slice(&data);
+
+        let inode = Inode::new(&inode_data).unwrap();
+
+        assert!(inode.is_dir());
+        assert_eq!(inode.file_type(), FileType::Directory);
+        assert_eq!(inode.mode() & 0o7777, 0o755);
+        assert_eq!(inode.uid(), 1000);
+        assert_eq!(inode.gid(), 100);
+        assert_eq!(inode.size(), 1024);
+        assert_eq!(inode.links_count(), 4);
+        assert_eq!(inode.sectors(), 2);
+        assert_eq!(inode.block(0), 0x4c);
+        assert_eq!(inode.block(1), 0);
+
+        let meta = inode.as_meta("root");
+        assert_eq!(meta.name, "root");
+        assert_eq!(
+            meta.modified.unwrap().format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2026-10-19 03:05:47"
+        );
+        assert_eq!(
+            meta.posix,
+            Some(PosixMetadata {
+                mode: 0o40755,
+                uid: 1000,
+                gid: 100,
+                links: 4,
+            })
+        );
+
+        println!("{:#?}", inode);
+    }
+}
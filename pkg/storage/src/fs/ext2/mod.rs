@@ -0,0 +1,76 @@
+pub mod direntry;
+pub mod file;
+pub mod group;
+pub mod impls;
+pub mod inode;
+pub mod superblock;
+
+use crate::*;
+use direntry::DirEntry;
+use file::File;
+use group::BlockGroupDescriptor;
+use inode::Inode;
+use superblock::Ext2SuperBlock;
+
+const BLOCK_SIZE: usize = 512;
+
+/// The inode of the root directory
+const ROOT_INODE: u32 = 2;
+
+/// Limit of nested symbolic links during a path lookup
+const MAX_SYMLINK_FOLLOWS: usize = 8;
+
+/// Identifies an ext2 filesystem on the disk.
+///
+/// The driver is read-only, it can also read ext3 volumes
+/// that have been cleanly unmounted as the journal is ignored.
+pub struct Ext2 {
+    handle: Ext2Handle,
+}
+
+impl Ext2 {
+    pub fn new(inner: impl BlockDevice<Block512>) -> Self {
+        Self {
+            handle: Arc::new(Ext2Impl::new(inner)),
+        }
+    }
+
+    /// Check whether the device holds an ext2 superblock
+    pub fn probe(inner: &impl BlockDevice<Block512>) -> bool {
+        let mut data = [0u8; Ext2SuperBlock::SIZE];
+        Ext2Impl::read_bytes(inner, Ext2SuperBlock::OFFSET, &mut data).is_ok()
+            && Ext2SuperBlock::new(&data).is_ok()
+    }
+}
+
+type Ext2Handle = Arc<Ext2Impl>;
+
+/// The ext2 filesystem.
+///
+/// [ Boot | SuperBlock ] [ Block Group 0 ] [ Block Group 1 ] ...
+///
+/// Each block group: [ (SuperBlock | Group Descriptors) | Bitmaps | Inode Table | Data ]
+pub struct Ext2Impl {
+    pub(crate) inner: Box<dyn BlockDevice<Block512>>,
+    pub sb: Ext2SuperBlock,
+    pub groups: Vec<BlockGroupDescriptor>,
+    pub block_size: usize,
+}
+
+impl core::fmt::Debug for Ext2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ext2")
+            .field("superblock", &self.handle.sb)
+            .field("groups", &self.handle.groups)
+            .finish()
+    }
+}
+
+impl core::fmt::Debug for Ext2Impl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ext2Impl")
+            .field("superblock", &self.sb)
+            .field("groups", &self.groups)
+            .finish()
+    }
+}
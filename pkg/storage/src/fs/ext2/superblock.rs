@@ -0,0 +1,205 @@
+//! Ext2 Superblock
+//!
+//! reference:
+//! - <https://www.nongnu.org/ext2-doc/ext2.html#superblock>
+//! - <https://wiki.osdev.org/Ext2#Superblock>
+
+/// Represents the superblock of an ext2 volume.
+///
+/// It is always located at byte 1024 from the beginning of the volume
+/// and is exactly 1024 bytes in length, regardless of the block size.
+pub struct Ext2SuperBlock {
+    data: [u8; 1024],
+}
+
+impl Ext2SuperBlock {
+    /// The byte offset of the superblock from the beginning of the volume
+    pub const OFFSET: usize = 1024;
+    /// The size of the superblock in bytes
+    pub const SIZE: usize = 1024;
+    pub const MAGIC: u16 = 0xEF53;
+
+    /// Directory entries record the file type
+    pub const FEATURE_INCOMPAT_FILETYPE: u32 = 0x0002;
+    /// Block groups are packed together, the layout is still read
+    /// from the group descriptors
+    pub const FEATURE_INCOMPAT_FLEX_BG: u32 = 0x0200;
+    /// Incompatible features that are understood by this driver
+    pub const FEATURE_INCOMPAT_SUPPORTED: u32 =
+        Self::FEATURE_INCOMPAT_FILETYPE | Self::FEATURE_INCOMPAT_FLEX_BG;
+
+    /// Attempt to parse the superblock from its 1024 bytes.
+    pub fn new(data: &[u8]) -> Result<Ext2SuperBlock, &'static str> {
+        let data = data.try_into().map_err(|_| "Bad superblock size")?;
+        let sb = Ext2SuperBlock { data };
+
+        if sb.magic() != Self::MAGIC {
+            return Err("Bad ext2 magic");
+        }
+
+        if sb.log_block_size() > 6 {
+            return Err("Bad ext2 block size");
+        }
+
+        if sb.blocks_per_group() == 0 || sb.inodes_per_group() == 0 {
+            return Err("Bad ext2 group size");
+        }
+
+        if sb.blocks_count().checked_sub(sb.first_data_block()).is_none() {
+            return Err("Bad ext2 block count");
+        }
+
+        if sb.feature_incompat() & !Self::FEATURE_INCOMPAT_SUPPORTED != 0 {
+            return Err("Unsupported ext2 incompatible features");
+        }
+
+        Ok(sb)
+    }
+
+    /// The size of a block in bytes, 1024 << s_log_block_size
+    pub fn block_size(&self) -> usize {
+        1024 << self.log_block_size()
+    }
+
+    /// Revision 0 uses fixed 128 byte inodes
+    pub fn inode_size(&self) -> usize {
+        if self.rev_level() == 0 {
+            128
+        } else {
+            self.inode_size_raw() as usize
+        }
+    }
+
+    /// The first inode that is not reserved
+    pub fn first_inode(&self) -> u32 {
+        if self.rev_level() == 0 {
+            11
+        } else {
+            self.first_ino()
+        }
+    }
+
+    /// The count of block groups on the volume
+    pub fn group_count(&self) -> usize {
+        let data_blocks = (self.blocks_count() - self.first_data_block()) as usize;
+        data_blocks.div_ceil(self.blocks_per_group() as usize)
+    }
+
+    /// Whether directory entries record the file type
+    pub fn has_filetype(&self) -> bool {
+        self.feature_incompat() & Self::FEATURE_INCOMPAT_FILETYPE != 0
+    }
+
+    define_field!(u32, 0x00, inodes_count);
+    define_field!(u32, 0x04, blocks_count);
+    define_field!(u32, 0x08, reserved_blocks_count);
+    define_field!(u32, 0x0C, free_blocks_count);
+    define_field!(u32, 0x10, free_inodes_count);
+    define_field!(u32, 0x14, first_data_block);
+    define_field!(u32, 0x18, log_block_size);
+    define_field!(u32, 0x1C, log_frag_size);
+    define_field!(u32, 0x20, blocks_per_group);
+    define_field!(u32, 0x24, frags_per_group);
+    define_field!(u32, 0x28, inodes_per_group);
+    define_field!(u32, 0x2C, mount_time);
+    define_field!(u32, 0x30, write_time);
+    define_field!(u16, 0x34, mount_count);
+    define_field!(u16, 0x36, max_mount_count);
+    define_field!(u16, 0x38, magic);
+    define_field!(u16, 0x3A, state);
+    define_field!(u16, 0x3C, errors);
+    define_field!(u16, 0x3E, minor_rev_level);
+    define_field!(u32, 0x40, last_check);
+    define_field!(u32, 0x44, check_interval);
+    define_field!(u32, 0x48, creator_os);
+    define_field!(u32, 0x4C, rev_level);
+    define_field!(u16, 0x50, default_reserved_uid);
+    define_field!(u16, 0x52, default_reserved_gid);
+    // -- EXT2_DYNAMIC_REV Specific --
+    define_field!(u32, 0x54, first_ino);
+    define_field!(u16, 0x58, inode_size_raw);
+    define_field!(u16, 0x5A, block_group_nr);
+    define_field!(u32, 0x5C, feature_compat);
+    define_field!(u32, 0x60, feature_incompat);
+    define_field!(u32, 0x64, feature_ro_compat);
+    define_field!([u8; 16], 0x68, uuid);
+    define_field!([u8; 16], 0x78, volume_name);
+    define_field!([u8; 64], 0x88, last_mounted);
+}
+
+impl core::fmt::Debug for Ext2SuperBlock {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ext2 SuperBlock")
+            .field("Inodes Count", &self.inodes_count())
+            .field("Blocks Count", &self.blocks_count())
+            .field("Free Blocks Count", &self.free_blocks_count())
+            .field("Free Inodes Count", &self.free_inodes_count())
+            .field("First Data Block", &self.first_data_block())
+            .field("Block Size", &self.block_size())
+            .field("Blocks per Group", &self.blocks_per_group())
+            .field("Inodes per Group", &self.inodes_per_group())
+            .field("Magic", &self.magic())
+            .field("State", &self.state())
+            .field("Revision Level", &self.rev_level())
+            .field("Inode Size", &self.inode_size())
+            .field("Feature Compat", &self.feature_compat())
+            .field("Feature Incompat", &self.feature_incompat())
+            .field("Feature RO Compat", &self.feature_ro_compat())
+            .field("UUID", &self.uuid())
+            .field("Volume Name", &self.volume_name_str().trim_end_matches('\0'))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ext2_superblock() {
+        let data = hex_literal::hex!(
+            "00 01 00 00 00 08 00 00 66 00 00 00 73 06 00 00
+             ee 00 00 00 01 00 00 00 00 00 00 00 00 00 00 00
+             00 20 00 00 00 20 00 00 00 01 00 00 00 00 00 00
+             0b 89 d5 6a 00 00 ff ff 53 ef 01 00 01 00 00 00
+             0b 89 d5 6a 00 00 00 00 00 00 00 00 01 00 00 00
+             00 00 00 00 0b 00 00 00 00 01 00 00 38 00 00 00
+             02 00 00 00 03 00 00 00 11 11 11 11 22 22 33 33
+             44 44 55 55 55 55 55 55 74 65 73 74 76 6f 6c 00"
+        );
+
+        let mut sb_data = [0u8; 1024];
+        sb_data[..data.len()].copy_from_slice(&data);
+
+        let sb = Ext2SuperBlock::new(&sb_data).unwrap();
+
+        assert_eq!(sb.inodes_count(), 256);
+        assert_eq!(sb.blocks_count(), 2048);
+        assert_eq!(sb.free_blocks_count(), 1651);
+        assert_eq!(sb.free_inodes_count(), 238);
+        assert_eq!(sb.first_data_block(), 1);
+        assert_eq!(sb.block_size(), 1024);
+        assert_eq!(sb.blocks_per_group(), 8192);
+        assert_eq!(sb.inodes_per_group(), 256);
+        assert_eq!(sb.group_count(), 1);
+        assert_eq!(sb.rev_level(), 1);
+        assert_eq!(sb.first_inode(), 11);
+        assert_eq!(sb.inode_size(), 256);
+        assert!(sb.has_filetype());
+        assert_eq!(
+            sb.uuid(),
+            &hex_literal::hex!("11 11 11 11 22 22 33 33 44 44 55 55 55 55 55 55")
+        );
+        assert_eq!(sb.volume_name_str().trim_end_matches('\0'), "testvol");
+
+        println!("{:#?}", sb);
+
+        // fewer blocks than the first data block
+        let mut bad_count = sb_data;
+        bad_count[0x04..0x08].fill(0);
+        assert!(Ext2SuperBlock::new(&bad_count).is_err());
+
+        sb_data[0x38] = 0;
+        assert!(Ext2SuperBlock::new(&sb_data).is_err());
+    }
+}
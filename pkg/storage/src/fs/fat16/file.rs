@@ -8,8 +8,8 @@ use super::*;
 pub struct File {
     /// The current offset in the file
     offset: usize,
-    /// The current cluster of this file
-    current_cluster: Cluster,
+    /// The clusters of this file walked so far, in chain order
+    clusters: Vec<Cluster>,
     /// DirEntry of this file
     entry: DirEntry,
     /// The file system handle that contains this file
@@ -20,7 +20,7 @@ impl File {
     pub fn new(handle: Fat16Handle, entry: DirEntry) -> Self {
         Self {
             offset: 0,
-            current_cluster: entry.cluster,
+            clusters: vec![entry.cluster],
             entry,
             handle,
         }
@@ -29,6 +29,20 @@ impl File {
     pub fn length(&self) -> usize {
         self.entry.size as usize
     }
+
+    /// Get the `index`-th cluster of the file
+    ///
+    /// The chain is walked only as far as needed and cached,
+    /// so seeking backwards never touches the FAT again.
+    fn cluster_at(&mut self, index: usize) -> Result<Cluster> {
+        while self.clusters.len() <= index {
+            let last = *self.clusters.last().unwrap();
+            let next = self.handle.read_next_cluster(last)?;
+            self.clusters.push(next);
+        }
+
+        Ok(self.clusters[index])
+    }
 }
 
 impl Read for File {
@@ -42,25 +56,31 @@ impl Read for File {
         //      - use `self.handle.cluster_to_sector` to convert cluster to sector
         //      - update `self.offset` after reading
         //      - update `self.cluster` with FAT if necessary
+        let sector_size = self.handle.bpb.bytes_per_sector() as usize;
+        let cluster_size = sector_size * self.handle.bpb.sectors_per_cluster() as usize;
         let mut total_read = 0;
 
         // 在文件结尾或者缓冲区已满时停止
-        while total_read < buf.len() && self.offset < self.entry.size as usize {
-            let sector_size = self.handle.bpb.bytes_per_sector() as usize;
-            let cluster_size = sector_size * self.handle.bpb.sectors_per_cluster() as usize;
+        while total_read < buf.len() && self.offset < self.length() {
             let cluster_offset = self.offset % cluster_size;
             let sector_offset = cluster_offset / sector_size;
             let in_sector_offset = self.offset % sector_size;
 
-            // 获取当前读取位置的扇区号
-            let sector = self.handle.cluster_to_sector(&self.current_cluster) + sector_offset;
+            // 获取当前读取位置的扇区号，必要时沿 FAT 查找簇
+            let cluster = match self.cluster_at(self.offset / cluster_size) {
+                Ok(cluster) => cluster,
+                // the chain may end before the recorded size
+                Err(_) if total_read > 0 => break,
+                Err(e) => return Err(e),
+            };
+            let sector = self.handle.cluster_to_sector(&cluster) + sector_offset;
 
             let mut sector_buf = Block::new(&[0u8; BLOCK_SIZE]);
             self.handle.inner.read_block(sector, &mut sector_buf)?;
 
             // 计算可以从当前扇区读取的字节数
             let remaining_in_sector = sector_size - in_sector_offset;
-            let remaining_in_file = self.entry.size as usize - self.offset;
+            let remaining_in_file = self.length() - self.offset;
             let to_read = remaining_in_sector.min(buf.len() - total_read).min(remaining_in_file);
 
             // 从扇区缓冲区复制数据到输出缓冲区
@@ -69,24 +89,17 @@ impl Read for File {
             // 更新偏移和总读取量
             self.offset += to_read;
             total_read += to_read;
-
-            // 如果在簇的末尾，移动到下一个簇
-            if cluster_offset + to_read == cluster_size {
-                match self.handle.read_next_cluster(self.current_cluster) {
-                    Ok(new_cluster) if new_cluster != Cluster::END_OF_FILE => self.current_cluster = new_cluster,
-                    _ => break, // 文件结尾或出错
-                }
-            }
         }
 
         Ok(total_read)
     }
 }
 
-// NOTE: `Seek` trait is not required for this lab
 impl Seek for File {
-    fn seek(&mut self, _pos: SeekFrom) -> Result<usize> {
-        unimplemented!()
+    /// Seeking past the end is allowed, reads there return 0 bytes
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize> {
+        self.offset = seek_offset(pos, self.offset, self.length())?;
+        Ok(self.offset)
     }
 }
 
@@ -7,6 +7,8 @@ impl Fat16Impl {
 
         inner.read_block(0, &mut block).unwrap();
         let bpb = Fat16Bpb::new(block.as_ref()).unwrap();
+        // FAT12 shares the layout of FAT16 except for the width of FAT entries
+        let fat_type = FatType::detect(block.as_ref()).unwrap_or(FatType::Fat16);
 
         trace!("Loading Fat16 Volume: {:#?}", bpb);
 
@@ -26,6 +28,7 @@ impl Fat16Impl {
         Self {
             bpb,
             inner: Box::new(inner),
+            fat_type,
             fat_start,
             first_data_sector,
             first_root_dir_sector,
@@ -54,6 +57,10 @@ impl Fat16Impl {
     //      - ...
     //      - finally, implement the FileSystem trait for Fat16 with `self.handle`
     pub fn read_next_cluster(&self, current_cluster: Cluster) -> Result<Cluster> {
+        if self.fat_type == FatType::Fat12 {
+            return self.read_next_cluster_fat12(current_cluster);
+        }
+
         let fat_offset = current_cluster.0 as usize * 2; // 每个 FAT16 表项 2 字节
         let fat_sector = self.fat_start + fat_offset / Block512::size();
         let ent_offset = fat_offset % Block512::size();
@@ -73,6 +80,38 @@ impl Fat16Impl {
         }
     }
 
+    /// FAT12 packs two 12-bit entries into three bytes,
+    /// so an entry may span two sectors.
+    fn read_next_cluster_fat12(&self, current_cluster: Cluster) -> Result<Cluster> {
+        let fat_offset = current_cluster.0 as usize * 3 / 2;
+        let fat_sector = self.fat_start + fat_offset / Block512::size();
+        let ent_offset = fat_offset % Block512::size();
+
+        let mut block = Block::default();
+        self.inner.read_block(fat_sector, &mut block)?;
+
+        let low = block[ent_offset];
+        let high = if ent_offset + 1 < Block512::size() {
+            block[ent_offset + 1]
+        } else {
+            self.inner.read_block(fat_sector + 1, &mut block)?;
+            block[0]
+        };
+
+        let value = u16::from_le_bytes([low, high]);
+        let next_cluster = if current_cluster.0 & 1 == 1 {
+            value >> 4
+        } else {
+            value & 0x0FFF
+        };
+
+        if next_cluster >= 0xFF8 {
+            Err(FsError::EndOfFile)
+        } else {
+            Ok(Cluster(next_cluster.into()))
+        }
+    }
+
     // 基于目录项名称查找 DirEntry
     fn find_entry_by_name(&self, dir: &Directory, name: &str) -> Result<DirEntry> {
         let mut entries = Vec::new();
@@ -38,6 +38,7 @@ type Fat16Handle = Arc<Fat16Impl>;
 pub struct Fat16Impl {
     pub(crate) inner: Box<dyn BlockDevice<Block512>>,
     pub bpb: Fat16Bpb,
+    pub fat_type: FatType,
     pub fat_start: usize,
     pub first_data_sector: usize,
     pub first_root_dir_sector: usize,
@@ -0,0 +1,169 @@
+//! Fat32 BIOS Parameter Block
+//!
+//! reference:
+//! - <https://en.wikipedia.org/wiki/Design_of_the_FAT_file_system#FAT32_Extended_BIOS_Parameter_Block>
+//! - <https://wiki.osdev.org/FAT#FAT_32>
+
+/// Represents a Boot Parameter Block with the FAT32 extensions.
+///
+/// This is the first sector of a FAT 32 formatted partition,
+/// the common part is the same as FAT 16, but the extended part
+/// describes the 32-bit FAT size, the root directory cluster and
+/// the location of the FSInfo sector.
+pub struct Fat32Bpb {
+    data: [u8; 512],
+}
+
+impl Fat32Bpb {
+    /// Attempt to parse a Boot Parameter Block from a 512 byte sector.
+    pub fn new(data: &[u8]) -> Result<Fat32Bpb, &'static str> {
+        let data = data.try_into().map_err(|_| "Bad BPB size")?;
+        let bpb = Fat32Bpb { data };
+
+        if bpb.trail() != 0xAA55 {
+            return Err("Bad BPB format");
+        }
+
+        if bpb.root_entries_count() != 0 || bpb.sectors_per_fat_16() != 0 {
+            return Err("Not a FAT32 BPB");
+        }
+
+        Ok(bpb)
+    }
+
+    pub fn total_sectors(&self) -> u32 {
+        if self.total_sectors_16() == 0 {
+            self.total_sectors_32()
+        } else {
+            self.total_sectors_16() as u32
+        }
+    }
+
+    /// Returns true if only one FAT is active (FAT mirroring disabled)
+    pub fn mirroring_disabled(&self) -> bool {
+        self.ext_flags() & 0x80 != 0
+    }
+
+    /// The zero-based index of the active FAT, only valid if mirroring is disabled
+    pub fn active_fat(&self) -> u8 {
+        (self.ext_flags() & 0x0F) as u8
+    }
+
+    define_field!([u8; 8], 0x03, oem_name);
+    define_field!(u16, 0x0B, bytes_per_sector);
+    define_field!(u8,  0x0D, sectors_per_cluster);
+    define_field!(u16, 0x0E, reserved_sector_count);
+    define_field!(u8,  0x10, fat_count);
+    define_field!(u16, 0x11, root_entries_count);
+    define_field!(u16, 0x13, total_sectors_16);
+    define_field!(u8,  0x15, media_descriptor);
+    define_field!(u16, 0x16, sectors_per_fat_16);
+    define_field!(u16, 0x18, sectors_per_track);
+    define_field!(u16, 0x1A, track_count);
+    define_field!(u32, 0x1C, hidden_sectors);
+    define_field!(u32, 0x20, total_sectors_32);
+    // FAT32 extended fields
+    define_field!(u32, 0x24, sectors_per_fat);
+    define_field!(u16, 0x28, ext_flags);
+    define_field!(u16, 0x2A, fs_version);
+    define_field!(u32, 0x2C, root_cluster);
+    define_field!(u16, 0x30, fs_info_sector);
+    define_field!(u16, 0x32, backup_boot_sector);
+    define_field!(u8,  0x40, drive_number);
+    define_field!(u8,  0x41, reserved_flags);
+    define_field!(u8,  0x42, boot_signature);
+    define_field!(u32, 0x43, volume_id);
+    define_field!([u8; 11], 0x47, volume_label);
+    define_field!([u8; 8], 0x52, system_identifier);
+    define_field!(u16, 0x1FE, trail);
+}
+
+impl core::fmt::Debug for Fat32Bpb {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fat32 BPB")
+            .field("OEM Name", &self.oem_name_str())
+            .field("Bytes per Sector", &self.bytes_per_sector())
+            .field("Sectors per Cluster", &self.sectors_per_cluster())
+            .field("Reserved Sector Count", &self.reserved_sector_count())
+            .field("FAT Count", &self.fat_count())
+            .field("Total Sectors", &self.total_sectors())
+            .field("Media Descriptor", &self.media_descriptor())
+            .field("Sectors per FAT", &self.sectors_per_fat())
+            .field("Sectors per Track", &self.sectors_per_track())
+            .field("Track Count", &self.track_count())
+            .field("Hidden Sectors", &self.hidden_sectors())
+            .field("Ext Flags", &self.ext_flags())
+            .field("FS Version", &self.fs_version())
+            .field("Root Cluster", &self.root_cluster())
+            .field("FSInfo Sector", &self.fs_info_sector())
+            .field("Backup Boot Sector", &self.backup_boot_sector())
+            .field("Drive Number", &self.drive_number())
+            .field("Boot Signature", &self.boot_signature())
+            .field("Volume ID", &self.volume_id())
+            .field("Volume Label", &self.volume_label_str())
+            .field("System Identifier", &self.system_identifier_str())
+            .field("Trail", &self.trail())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fat32_bpb() {
+        // Formatted by mkfs.fat with a 64 MiB image
+        const DATA: [u8; 90] = hex_literal::hex!(
+            "EB 58 90 6D 6B 66 73 2E 66 61 74 00 02 01 20 00
+        02 00 00 00 00 F8 00 00 20 00 08 00 00 00 00 00
+        00 00 02 00 F1 03 00 00 00 00 00 00 02 00 00 00
+        01 00 06 00 00 00 00 00 00 00 00 00 00 00 00 00
+        80 00 29 3B 8A 4F 1C 4E 4F 20 4E 41 4D 45 20 20
+        20 20 46 41 54 33 32 20 20 20"
+        );
+
+        const PADDING: &[u8] = concat_bytes!([0x00; 420], [0x55, 0xAA]);
+
+        let mut bpb_data = DATA.to_vec();
+        bpb_data.extend_from_slice(PADDING);
+
+        let bpb = Fat32Bpb::new(&bpb_data).unwrap();
+
+        assert_eq!(bpb.oem_name(), b"mkfs.fat");
+        assert_eq!(bpb.bytes_per_sector(), 512);
+        assert_eq!(bpb.sectors_per_cluster(), 1);
+        assert_eq!(bpb.reserved_sector_count(), 32);
+        assert_eq!(bpb.fat_count(), 2);
+        assert_eq!(bpb.root_entries_count(), 0);
+        assert_eq!(bpb.total_sectors_16(), 0);
+        assert_eq!(bpb.media_descriptor(), 0xf8);
+        assert_eq!(bpb.sectors_per_fat_16(), 0);
+        assert_eq!(bpb.sectors_per_track(), 32);
+        assert_eq!(bpb.track_count(), 8);
+        assert_eq!(bpb.hidden_sectors(), 0);
+        assert_eq!(bpb.total_sectors_32(), 0x20000);
+        assert_eq!(bpb.sectors_per_fat(), 1009);
+        assert_eq!(bpb.ext_flags(), 0);
+        assert!(!bpb.mirroring_disabled());
+        assert_eq!(bpb.fs_version(), 0);
+        assert_eq!(bpb.root_cluster(), 2);
+        assert_eq!(bpb.fs_info_sector(), 1);
+        assert_eq!(bpb.backup_boot_sector(), 6);
+        assert_eq!(bpb.drive_number(), 0x80);
+        assert_eq!(bpb.boot_signature(), 0x29);
+        assert_eq!(bpb.volume_id(), 0x1c4f8a3b);
+        assert_eq!(bpb.volume_label(), b"NO NAME    ");
+        assert_eq!(bpb.system_identifier(), b"FAT32   ");
+
+        assert_eq!(bpb.total_sectors(), 0x20000);
+        assert_eq!(crate::FatType::detect(&bpb_data), Some(crate::FatType::Fat32));
+
+        // the drivers only read 512-byte sectors
+        let mut large_sectors = bpb_data.clone();
+        large_sectors[0x0B..0x0D].copy_from_slice(&1024u16.to_le_bytes());
+        assert_eq!(crate::FatType::detect(&large_sectors), None);
+
+        println!("{:#?}", bpb);
+    }
+}
@@ -0,0 +1,95 @@
+//! File
+//!
+//! reference: <https://wiki.osdev.org/FAT#Directories_on_FAT12.2F16.2F32>
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The current offset in the file
+    offset: usize,
+    /// The clusters of this file walked so far, in chain order
+    clusters: Vec<Cluster>,
+    /// DirEntry of this file
+    entry: DirEntry,
+    /// The file system handle that contains this file
+    handle: Fat32Handle,
+}
+
+impl File {
+    pub fn new(handle: Fat32Handle, entry: DirEntry) -> Self {
+        Self {
+            offset: 0,
+            clusters: vec![entry.cluster],
+            entry,
+            handle,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.entry.size as usize
+    }
+
+    /// Get the `index`-th cluster of the file, walking the chain if needed
+    fn cluster_at(&mut self, index: usize) -> Result<Cluster> {
+        while self.clusters.len() <= index {
+            let last = *self.clusters.last().unwrap();
+            let next = self.handle.read_next_cluster(last)?;
+            self.clusters.push(next);
+        }
+
+        Ok(self.clusters[index])
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let cluster_size = self.handle.cluster_size();
+        let mut total_read = 0;
+        let mut block = Block::default();
+
+        while total_read < buf.len() && self.offset < self.length() {
+            let cluster_offset = self.offset % cluster_size;
+            let sector_offset = cluster_offset / BLOCK_SIZE;
+            let in_sector_offset = self.offset % BLOCK_SIZE;
+
+            let cluster = match self.cluster_at(self.offset / cluster_size) {
+                Ok(cluster) => cluster,
+                // the chain may end before the recorded size
+                Err(_) if total_read > 0 => break,
+                Err(e) => return Err(e),
+            };
+            let sector = self.handle.cluster_to_sector(&cluster) + sector_offset;
+            self.handle.inner.read_block(sector, &mut block)?;
+
+            let to_read = (BLOCK_SIZE - in_sector_offset)
+                .min(buf.len() - total_read)
+                .min(self.length() - self.offset);
+
+            buf[total_read..total_read + to_read]
+                .copy_from_slice(&block[in_sector_offset..in_sector_offset + to_read]);
+
+            self.offset += to_read;
+            total_read += to_read;
+        }
+
+        Ok(total_read)
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize> {
+        self.offset = seek_offset(pos, self.offset, self.length())?;
+        Ok(self.offset)
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, _buf: &[u8]) -> Result<usize> {
+        Err(FsError::ReadOnly)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Err(FsError::ReadOnly)
+    }
+}
@@ -0,0 +1,87 @@
+//! Fat32 FSInfo Sector
+//!
+//! reference: <https://en.wikipedia.org/wiki/Design_of_the_FAT_file_system#FS_Information_Sector>
+
+/// Represents the FSInfo sector of a FAT32 volume.
+///
+/// It caches the count of free clusters and the hint of the next free cluster,
+/// both values may be unknown (`0xFFFFFFFF`) and are only advisory.
+pub struct FsInfo {
+    data: [u8; 512],
+}
+
+impl FsInfo {
+    pub const LEAD_SIGNATURE: u32 = 0x4161_5252;
+    pub const STRUCT_SIGNATURE: u32 = 0x6141_7272;
+    pub const TRAIL_SIGNATURE: u32 = 0xAA55_0000;
+    const UNKNOWN: u32 = 0xFFFF_FFFF;
+
+    /// Attempt to parse the FSInfo from a 512 byte sector.
+    pub fn new(data: &[u8]) -> Result<FsInfo, &'static str> {
+        let data = data.try_into().map_err(|_| "Bad FSInfo size")?;
+        let info = FsInfo { data };
+
+        if info.lead_signature() != Self::LEAD_SIGNATURE
+            || info.struct_signature() != Self::STRUCT_SIGNATURE
+            || info.trail_signature() != Self::TRAIL_SIGNATURE
+        {
+            return Err("Bad FSInfo signature");
+        }
+
+        Ok(info)
+    }
+
+    /// The last known count of free clusters, if any
+    pub fn free_clusters(&self) -> Option<u32> {
+        Some(self.free_count()).filter(|&count| count != Self::UNKNOWN)
+    }
+
+    /// The cluster from which to start looking for free clusters, if any
+    pub fn next_free_cluster(&self) -> Option<u32> {
+        Some(self.next_free()).filter(|&next| next != Self::UNKNOWN && next >= 2)
+    }
+
+    define_field!(u32, 0x000, lead_signature);
+    define_field!(u32, 0x1E4, struct_signature);
+    define_field!(u32, 0x1E8, free_count);
+    define_field!(u32, 0x1EC, next_free);
+    define_field!(u32, 0x1FC, trail_signature);
+}
+
+impl core::fmt::Debug for FsInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fat32 FSInfo")
+            .field("Free Clusters", &self.free_clusters())
+            .field("Next Free Cluster", &self.next_free_cluster())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fsinfo() {
+        let mut data = [0u8; 512];
+        data[0x000..0x004].copy_from_slice(&hex_literal::hex!("52 52 61 41"));
+        data[0x1E4..0x1E8].copy_from_slice(&hex_literal::hex!("72 72 41 61"));
+        data[0x1E8..0x1EC].copy_from_slice(&hex_literal::hex!("F9 F7 01 00"));
+        data[0x1EC..0x1F0].copy_from_slice(&hex_literal::hex!("05 00 00 00"));
+        data[0x1FC..0x200].copy_from_slice(&hex_literal::hex!("00 00 55 AA"));
+
+        let info = FsInfo::new(&data).unwrap();
+
+        assert_eq!(info.free_clusters(), Some(0x1f7f9));
+        assert_eq!(info.next_free_cluster(), Some(5));
+
+        data[0x1E8..0x1F0].copy_from_slice(&[0xFF; 8]);
+        let info = FsInfo::new(&data).unwrap();
+
+        assert_eq!(info.free_clusters(), None);
+        assert_eq!(info.next_free_cluster(), None);
+
+        data[0x1FC] = 0x01;
+        assert!(FsInfo::new(&data).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use super::*;
+
+impl Fat32Impl {
+    pub fn new(inner: impl BlockDevice<Block512>) -> Self {
+        let mut block = Block::default();
+
+        inner.read_block(0, &mut block).unwrap();
+        let bpb = Fat32Bpb::new(block.as_ref()).unwrap();
+
+        trace!("Loading Fat32 Volume: {:#?}", bpb);
+
+        let fsinfo = match bpb.fs_info_sector() {
+            0 | 0xFFFF => None,
+            sector => inner
+                .read_block(sector as usize, &mut block)
+                .ok()
+                .and_then(|_| FsInfo::new(block.as_ref()).ok()),
+        };
+
+        if fsinfo.is_none() {
+            warn!("Fat32 volume has no valid FSInfo sector");
+        }
+
+        // when mirroring is disabled, only the active FAT is up to date
+        let active_fat = if bpb.mirroring_disabled() {
+            bpb.active_fat() as usize
+        } else {
+            0
+        };
+        let fat_start =
+            bpb.reserved_sector_count() as usize + active_fat * bpb.sectors_per_fat() as usize;
+        let first_data_sector = bpb.reserved_sector_count() as usize
+            + bpb.fat_count() as usize * bpb.sectors_per_fat() as usize;
+        let root_cluster = Cluster(bpb.root_cluster());
+
+        Self {
+            bpb,
+            fsinfo,
+            inner: Box::new(inner),
+            fat_start,
+            first_data_sector,
+            root_cluster,
+        }
+    }
+
+    pub fn cluster_to_sector(&self, cluster: &Cluster) -> usize {
+        // HINT: FirstSectorofCluster = ((N – 2) * BPB_SecPerClus) + FirstDataSector;
+        (cluster.0 as usize - 2) * self.bpb.sectors_per_cluster() as usize + self.first_data_sector
+    }
+
+    pub fn cluster_size(&self) -> usize {
+        self.bpb.sectors_per_cluster() as usize * BLOCK_SIZE
+    }
+
+    /// Returns the count of free clusters recorded in the FSInfo sector
+    pub fn free_clusters(&self) -> Option<u32> {
+        self.fsinfo.as_ref().and_then(FsInfo::free_clusters)
+    }
+
+    /// Read the FAT entry of the cluster, only the low 28 bits are used
+    pub fn read_next_cluster(&self, current_cluster: Cluster) -> Result<Cluster> {
+        let fat_offset = current_cluster.0 as usize * 4; // 每个 FAT32 表项 4 字节
+        let fat_sector = self.fat_start + fat_offset / BLOCK_SIZE;
+        let ent_offset = fat_offset % BLOCK_SIZE;
+
+        let mut block = Block::default();
+        self.inner.read_block(fat_sector, &mut block)?;
+
+        let next_cluster =
+            u32::from_le_bytes(block[ent_offset..ent_offset + 4].try_into().unwrap()) & 0x0FFF_FFFF;
+
+        match next_cluster {
+            0x0FFF_FFF8..=0x0FFF_FFFF => Err(FsError::EndOfFile),
+            0x0FFF_FFF7 => Err(FsError::BadCluster),
+            0 | 1 => Err(FsError::BadCluster),
+            n => Ok(Cluster(n)),
+        }
+    }
+
+    fn root_dir(&self) -> Directory {
+        Directory::new(self.root_cluster)
+    }
+
+    /// The `..` entry of a first level directory points to cluster 0
+    fn dir_from_entry(&self, entry: DirEntry) -> Directory {
+        if entry.cluster == Cluster::EMPTY {
+            self.root_dir()
+        } else {
+            Directory::from_entry(entry)
+        }
+    }
+
+    fn find_entry_by_name(&self, dir: &Directory, name: &str) -> Result<DirEntry> {
+        let mut found = None;
+
+        self.iterate_dir(dir, |entry| {
+            if found.is_none() && entry.filename() == name {
+                found = Some(entry.clone());
+            }
+        })?;
+
+        found.ok_or(FsError::FileNotFound)
+    }
+
+    pub fn find_dir(&self, path: &str) -> Result<Directory> {
+        let mut current_dir = self.root_dir();
+
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            let entry = self.find_entry_by_name(&current_dir, &part.to_uppercase())?;
+            if !entry.is_directory() {
+                return Err(FsError::NotADirectory);
+            }
+            current_dir = self.dir_from_entry(entry);
+        }
+
+        Ok(current_dir)
+    }
+
+    pub fn find_entry(&self, path: &str) -> Result<DirEntry> {
+        debug!("Searching for {:?}", path);
+
+        let path = path.trim_end_matches('/');
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (parent, name),
+            None => ("", path),
+        };
+
+        if name.is_empty() {
+            // the root directory has no entry
+            return Err(FsError::FileNotFound);
+        }
+
+        let dir = self.find_dir(parent)?;
+        self.find_entry_by_name(&dir, &name.to_uppercase())
+    }
+
+    pub fn iterate_dir<F>(&self, dir: &Directory, mut func: F) -> Result<()>
+    where
+        F: FnMut(&DirEntry),
+    {
+        if let Some(entry) = &dir.entry {
+            trace!("Iterating directory: {}", entry.filename());
+        }
+
+        let mut current_cluster = Some(dir.cluster);
+        let mut block = Block::default();
+
+        while let Some(cluster) = current_cluster {
+            let first_sector = self.cluster_to_sector(&cluster);
+            for sector in first_sector..first_sector + self.bpb.sectors_per_cluster() as usize {
+                self.inner.read_block(sector, &mut block)?;
+                for entry in 0..BLOCK_SIZE / DirEntry::LEN {
+                    let start = entry * DirEntry::LEN;
+                    let end = (entry + 1) * DirEntry::LEN;
+
+                    let dir_entry = DirEntry::parse(&block[start..end])?;
+
+                    if dir_entry.filename.is_eod() {
+                        return Ok(());
+                    } else if dir_entry.is_valid()
+                        && !dir_entry.is_long_name()
+                        && !dir_entry.attributes.contains(Attributes::VOLUME_ID)
+                    {
+                        func(&dir_entry);
+                    }
+                }
+            }
+            current_cluster = self.read_next_cluster(cluster).ok();
+        }
+
+        Ok(())
+    }
+
+    fn read_dir(&self, dir: &Directory) -> Result<Vec<Metadata>> {
+        let mut entries = Vec::new();
+
+        self.iterate_dir(dir, |entry| {
+            entries.push(entry.as_meta());
+        })?;
+
+        Ok(entries)
+    }
+}
+
+impl FileSystem for Fat32 {
+    fn read_dir(&self, path: &str) -> Result<Box<dyn Iterator<Item = Metadata> + Send>> {
+        let dir = self.handle.find_dir(path)?;
+        let entries = self.handle.read_dir(&dir)?;
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn open_file(&self, path: &str) -> Result<FileHandle> {
+        let entry = self.handle.find_entry(path)?;
+        if entry.is_directory() {
+            return Err(FsError::NotAFile);
+        }
+        let meta = (&entry).into();
+        let file = File::new(self.handle.clone(), entry);
+        Ok(FileHandle::new(meta, Box::new(file)))
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata> {
+        let entry = self.handle.find_entry(path)?;
+        Ok((&entry).into())
+    }
+
+    fn exists(&self, path: &str) -> Result<bool> {
+        match self.handle.find_entry(path) {
+            Ok(_) => Ok(true),
+            Err(FsError::FileNotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+pub mod bpb;
+pub mod file;
+pub mod fsinfo;
+pub mod impls;
+
+use crate::*;
+use crate::fat16::directory::Directory;
+use crate::fat16::direntry::*;
+use file::File;
+
+use bpb::Fat32Bpb;
+use fsinfo::FsInfo;
+
+const BLOCK_SIZE: usize = 512;
+
+/// Identifies a Fat32 filesystem on the disk.
+pub struct Fat32 {
+    handle: Fat32Handle,
+}
+
+impl Fat32 {
+    pub fn new(inner: impl BlockDevice<Block512>) -> Self {
+        Self {
+            handle: Arc::new(Fat32Impl::new(inner)),
+        }
+    }
+}
+
+type Fat32Handle = Arc<Fat32Impl>;
+
+/// The Fat32 filesystem.
+///
+/// Unlike Fat16, there is no fixed root directory region,
+/// the root directory is a cluster chain starting at `root_cluster`.
+///
+/// [ Fat32 BPB | FSInfo | Reserved ] [ FATs ] [ Data ]
+pub struct Fat32Impl {
+    pub(crate) inner: Box<dyn BlockDevice<Block512>>,
+    pub bpb: Fat32Bpb,
+    pub fsinfo: Option<FsInfo>,
+    pub fat_start: usize,
+    pub first_data_sector: usize,
+    pub root_cluster: Cluster,
+}
+
+impl core::fmt::Debug for Fat32 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fat32")
+            .field("bpb", &self.handle.bpb)
+            .field("fsinfo", &self.handle.fsinfo)
+            .finish()
+    }
+}
+
+impl core::fmt::Debug for Fat32Impl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Fat32Impl")
+            .field("bpb", &self.bpb)
+            .field("fsinfo", &self.fsinfo)
+            .finish()
+    }
+}
@@ -1 +1,68 @@
+pub mod ext2;
 pub mod fat16;
+pub mod fat32;
+pub mod tmpfs;
+
+/// The FAT variant of a volume
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FatType {
+    Fat12,
+    Fat16,
+    Fat32,
+}
+
+impl FatType {
+    /// Detect the FAT variant from the boot sector of a volume.
+    ///
+    /// The variant is decided only by the count of data clusters,
+    /// returns `None` if the sector does not look like a FAT boot sector.
+    ///
+    /// reference: <https://wiki.osdev.org/FAT#Determining_FAT_type>
+    pub fn detect(boot_sector: &[u8]) -> Option<FatType> {
+        if boot_sector.len() < 512 || boot_sector[0x1FE..0x200] != [0x55, 0xAA] {
+            return None;
+        }
+
+        let u16_at = |offset: usize| u16::from_le_bytes([boot_sector[offset], boot_sector[offset + 1]]);
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes(boot_sector[offset..offset + 4].try_into().unwrap())
+        };
+
+        let bytes_per_sector = u16_at(0x0B) as usize;
+        let sectors_per_cluster = boot_sector[0x0D] as usize;
+        let reserved_sectors = u16_at(0x0E) as usize;
+        let fat_count = boot_sector[0x10] as usize;
+        let root_entries = u16_at(0x11) as usize;
+
+        // the drivers read the volume by 512-byte blocks
+        if bytes_per_sector != 512
+            || !sectors_per_cluster.is_power_of_two()
+            || reserved_sectors == 0
+            || fat_count == 0
+        {
+            return None;
+        }
+
+        let total_sectors = match u16_at(0x13) {
+            0 => u32_at(0x20) as usize,
+            n => n as usize,
+        };
+        let fat_size = match u16_at(0x16) {
+            0 => u32_at(0x24) as usize,
+            n => n as usize,
+        };
+        let root_dir_sectors = (root_entries * 32).div_ceil(bytes_per_sector);
+        let meta_sectors = reserved_sectors + fat_count * fat_size + root_dir_sectors;
+
+        let data_sectors = total_sectors.checked_sub(meta_sectors)?;
+        let cluster_count = data_sectors / sectors_per_cluster;
+
+        Some(if cluster_count < 4085 {
+            FatType::Fat12
+        } else if cluster_count < 65525 {
+            FatType::Fat16
+        } else {
+            FatType::Fat32
+        })
+    }
+}
@@ -0,0 +1,77 @@
+//! File
+//!
+//! The content is kept in a `Vec<u8>`, writing past the end grows it
+//! and the gap left by a seek is filled with zeros.
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The current offset in the file
+    offset: usize,
+    /// Whether to write at the end of the file regardless of the offset
+    append: bool,
+    /// The content of this file
+    data: FileData,
+}
+
+impl File {
+    pub fn new(data: FileData, append: bool) -> Self {
+        Self {
+            offset: 0,
+            append,
+            data,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.data.read().len()
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let data = self.data.read();
+
+        if self.offset >= data.len() {
+            return Ok(0);
+        }
+
+        let len = buf.len().min(data.len() - self.offset);
+        buf[..len].copy_from_slice(&data[self.offset..self.offset + len]);
+        self.offset += len;
+
+        Ok(len)
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize> {
+        self.offset = seek_offset(pos, self.offset, self.length())?;
+        Ok(self.offset)
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut data = self.data.write();
+
+        if self.append {
+            self.offset = data.len();
+        }
+
+        let end = self.offset + buf.len();
+        if end > data.len() {
+            data.resize(end, 0);
+        }
+
+        data[self.offset..end].copy_from_slice(buf);
+        self.offset = end;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+use super::*;
+
+/// Split a path into its components, `.` and `..` are resolved lexically
+fn components(path: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+
+    parts
+}
+
+/// Split a path into the components of its parent and its name
+fn split_parent(path: &str) -> Result<(Vec<&str>, &str)> {
+    let mut parts = components(path);
+    match parts.pop() {
+        Some(name) => Ok((parts, name)),
+        // the root directory has no parent
+        None => Err(FsError::InvalidOperation),
+    }
+}
+
+impl Directory {
+    fn dir(&self, parts: &[&str]) -> Result<&Directory> {
+        let mut dir = self;
+
+        for part in parts {
+            dir = match dir.entries.get(*part) {
+                Some(Node::Directory(child)) => child,
+                Some(_) => return Err(FsError::NotADirectory),
+                None => return Err(FsError::FileNotFound),
+            };
+        }
+
+        Ok(dir)
+    }
+
+    fn dir_mut(&mut self, parts: &[&str]) -> Result<&mut Directory> {
+        let mut dir = self;
+
+        for part in parts {
+            dir = match dir.entries.get_mut(*part) {
+                Some(Node::Directory(child)) => child,
+                Some(_) => return Err(FsError::NotADirectory),
+                None => return Err(FsError::FileNotFound),
+            };
+        }
+
+        Ok(dir)
+    }
+
+    fn node(&self, parts: &[&str], name: &str) -> Result<&Node> {
+        self.dir(parts)?
+            .entries
+            .get(name)
+            .ok_or(FsError::FileNotFound)
+    }
+
+    fn file(&self, parts: &[&str], name: &str) -> Result<&FileData> {
+        match self.node(parts, name)? {
+            Node::File(data) => Ok(data),
+            _ => Err(FsError::NotAFile),
+        }
+    }
+
+    /// Get the file to write to, create it if it does not exist
+    fn file_or_create(&mut self, path: &str) -> Result<FileData> {
+        let (parts, name) = split_parent(path)?;
+        let dir = self.dir_mut(&parts)?;
+
+        match dir
+            .entries
+            .entry(name.to_owned())
+            .or_insert_with(|| Node::File(FileData::default()))
+        {
+            Node::File(data) => Ok(data.clone()),
+            _ => Err(FsError::NotAFile),
+        }
+    }
+
+    /// Detach the entry from its parent directory
+    fn take(&mut self, parts: &[&str], name: &str) -> Result<Node> {
+        self.dir_mut(parts)?
+            .entries
+            .remove(name)
+            .ok_or(FsError::FileNotFound)
+    }
+}
+
+impl FileSystem for TmpFs {
+    fn read_dir(&self, path: &str) -> Result<Box<dyn Iterator<Item = Metadata> + Send>> {
+        let root = self.root.read();
+        let dir = root.dir(&components(path))?;
+
+        let entries: Vec<Metadata> = dir
+            .entries
+            .iter()
+            .map(|(name, node)| node.as_meta(name))
+            .collect();
+
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn open_file(&self, path: &str) -> Result<FileHandle> {
+        let (parts, name) = split_parent(path).map_err(|_| FsError::NotAFile)?;
+        let data = self.root.read().file(&parts, name)?.clone();
+
+        let meta = Node::File(data.clone()).as_meta(name);
+        Ok(FileHandle::new(meta, Box::new(File::new(data, false))))
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata> {
+        let root = self.root.read();
+
+        match split_parent(path) {
+            Ok((parts, name)) => Ok(root.node(&parts, name)?.as_meta(name)),
+            Err(_) => Ok(Metadata::new(
+                "/".into(),
+                FileType::Directory,
+                0,
+                None,
+                None,
+                None,
+            )),
+        }
+    }
+
+    fn exists(&self, path: &str) -> Result<bool> {
+        match self.metadata(path) {
+            Ok(_) => Ok(true),
+            Err(FsError::FileNotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create_file(&self, path: &str) -> Result<FileHandle> {
+        let data = self.root.write().file_or_create(path)?;
+        data.write().clear();
+
+        let (_, name) = split_parent(path)?;
+        let meta = Node::File(data.clone()).as_meta(name);
+        Ok(FileHandle::new(meta, Box::new(File::new(data, false))))
+    }
+
+    fn append_file(&self, path: &str) -> Result<FileHandle> {
+        let data = self.root.write().file_or_create(path)?;
+
+        let (_, name) = split_parent(path)?;
+        let meta = Node::File(data.clone()).as_meta(name);
+        Ok(FileHandle::new(meta, Box::new(File::new(data, true))))
+    }
+
+    fn create_dir(&self, path: &str) -> Result<()> {
+        let (parts, name) = split_parent(path).map_err(|_| FsError::AlreadyExists)?;
+        let mut root = self.root.write();
+        let dir = root.dir_mut(&parts)?;
+
+        if dir.entries.contains_key(name) {
+            return Err(FsError::AlreadyExists);
+        }
+
+        dir.entries
+            .insert(name.to_owned(), Node::Directory(Directory::default()));
+        Ok(())
+    }
+
+    fn create_fifo(&self, path: &str) -> Result<()> {
+        let (parts, name) = split_parent(path).map_err(|_| FsError::AlreadyExists)?;
+        let mut root = self.root.write();
+        let dir = root.dir_mut(&parts)?;
+
+        if dir.entries.contains_key(name) {
+            return Err(FsError::AlreadyExists);
+        }
+
+        dir.entries.insert(name.to_owned(), Node::Fifo);
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &str) -> Result<()> {
+        let (parts, name) = split_parent(path).map_err(|_| FsError::NotAFile)?;
+        let mut root = self.root.write();
+
+        if let Node::Directory(_) = root.node(&parts, name)? {
+            return Err(FsError::NotAFile);
+        }
+
+        root.take(&parts, name)?;
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: &str) -> Result<()> {
+        let (parts, name) = split_parent(path)?;
+        let mut root = self.root.write();
+
+        match root.node(&parts, name)? {
+            Node::Directory(dir) if !dir.entries.is_empty() => {
+                return Err(FsError::DirectoryNotEmpty)
+            }
+            Node::Directory(_) => {}
+            _ => return Err(FsError::NotADirectory),
+        }
+
+        root.take(&parts, name)?;
+        Ok(())
+    }
+
+    fn copy_file(&self, src: &str, dst: &str) -> Result<()> {
+        let (parts, name) = split_parent(src).map_err(|_| FsError::NotAFile)?;
+        let content = self.root.read().file(&parts, name)?.read().clone();
+
+        let data = self.root.write().file_or_create(dst)?;
+        *data.write() = content;
+        Ok(())
+    }
+
+    fn move_file(&self, src: &str, dst: &str) -> Result<()> {
+        let (src_parts, src_name) = split_parent(src).map_err(|_| FsError::NotAFile)?;
+        let (dst_parts, dst_name) = split_parent(dst).map_err(|_| FsError::NotAFile)?;
+        let mut root = self.root.write();
+
+        // check both ends before detaching anything
+        if let Node::Directory(_) = root.node(&src_parts, src_name)? {
+            return Err(FsError::NotAFile);
+        }
+        if let Some(Node::Directory(_)) = root.dir(&dst_parts)?.entries.get(dst_name) {
+            return Err(FsError::NotAFile);
+        }
+
+        let node = root.take(&src_parts, src_name)?;
+        root.dir_mut(&dst_parts)?
+            .entries
+            .insert(dst_name.to_owned(), node);
+        Ok(())
+    }
+
+    fn move_dir(&self, src: &str, dst: &str) -> Result<()> {
+        let (src_parts, src_name) = split_parent(src)?;
+        let (dst_parts, dst_name) = split_parent(dst)?;
+        let mut root = self.root.write();
+
+        if !matches!(root.node(&src_parts, src_name)?, Node::Directory(_)) {
+            return Err(FsError::NotADirectory);
+        }
+
+        // a directory can not be moved into itself
+        let src_len = src_parts.len();
+        if dst_parts.len() > src_len
+            && dst_parts[..src_len] == src_parts[..]
+            && dst_parts[src_len] == src_name
+        {
+            return Err(FsError::InvalidOperation);
+        }
+
+        if root.dir(&dst_parts)?.entries.contains_key(dst_name) {
+            return Err(FsError::AlreadyExists);
+        }
+
+        let node = root.take(&src_parts, src_name)?;
+        root.dir_mut(&dst_parts)?
+            .entries
+            .insert(dst_name.to_owned(), node);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_to_vec(fs: &TmpFs, path: &str) -> Vec<u8> {
+        let mut buf = Vec::new();
+        fs.open_file(path).unwrap().read_all(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_tmpfs() {
+        let fs = TmpFs::new();
+
+        fs.create_dir("/dir").unwrap();
+        assert_eq!(fs.create_dir("/dir"), Err(FsError::AlreadyExists));
+
+        let mut file = fs.create_file("/dir/a.txt").unwrap();
+        file.write_all(b"hello").unwrap();
+        file.seek(SeekFrom::Start(7)).unwrap();
+        file.write_all(b"world").unwrap();
+        assert_eq!(read_to_vec(&fs, "/dir/a.txt"), b"hello\0\0world");
+
+        let mut file = fs.append_file("/dir/a.txt").unwrap();
+        file.write_all(b"!").unwrap();
+        assert_eq!(fs.metadata("/dir/./a.txt").unwrap().len, 13);
+
+        fs.create_file("/dir/a.txt").unwrap();
+        assert_eq!(read_to_vec(&fs, "/dir/a.txt"), b"");
+
+        fs.append_file("/b.txt").unwrap().write_all(b"b").unwrap();
+        fs.copy_file("/b.txt", "/dir/c.txt").unwrap();
+        fs.move_file("/b.txt", "/dir/a.txt").unwrap();
+        assert!(!fs.exists("/b.txt").unwrap());
+        assert_eq!(read_to_vec(&fs, "/dir/a.txt"), b"b");
+
+        let names: Vec<String> = fs.read_dir("/dir").unwrap().map(|m| m.name).collect();
+        assert_eq!(names, ["a.txt", "c.txt"]);
+
+        assert_eq!(fs.remove_dir("/dir"), Err(FsError::DirectoryNotEmpty));
+        assert_eq!(fs.move_dir("/dir", "/dir/sub"), Err(FsError::InvalidOperation));
+        fs.move_dir("/dir", "/new").unwrap();
+        assert!(fs.metadata("/new").unwrap().is_dir());
+
+        let mut opened = fs.open_file("/new/c.txt").unwrap();
+        fs.remove_file("/new/c.txt").unwrap();
+        fs.remove_file("/new/a.txt").unwrap();
+        fs.remove_dir("/new").unwrap();
+
+        let mut buf = Vec::new();
+        opened.read_all(&mut buf).unwrap();
+        assert_eq!(buf, b"b");
+
+        assert_eq!(fs.read_dir("/").unwrap().count(), 0);
+        assert!(fs.metadata("/").unwrap().is_dir());
+
+        fs.create_fifo("/fifo").unwrap();
+        assert_eq!(fs.create_fifo("/fifo"), Err(FsError::AlreadyExists));
+        assert!(fs.metadata("/fifo").unwrap().is_fifo());
+        assert_eq!(fs.open_file("/fifo").err(), Some(FsError::NotAFile));
+        assert_eq!(fs.create_dir("/fifo/dir"), Err(FsError::NotADirectory));
+        fs.move_file("/fifo", "/pipe").unwrap();
+        fs.remove_file("/pipe").unwrap();
+        assert!(!fs.exists("/pipe").unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+pub mod file;
+pub mod impls;
+
+use crate::*;
+use alloc::collections::BTreeMap;
+use file::File;
+use spin::RwLock;
+
+/// Content of a file, shared with all the open handles of it
+///
+/// A removed file stays readable through the handles opened before.
+type FileData = Arc<RwLock<Vec<u8>>>;
+
+/// A filesystem that lives in memory only.
+///
+/// All the content is lost when the filesystem is dropped,
+/// it is meant to be mounted at `/tmp` as the scratch space.
+#[derive(Default)]
+pub struct TmpFs {
+    root: RwLock<Directory>,
+}
+
+impl TmpFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// A node of the directory tree
+///
+/// A FIFO has no content here, the kernel connects its readers and writers.
+pub enum Node {
+    File(FileData),
+    Directory(Directory),
+    Fifo,
+}
+
+#[derive(Default)]
+pub struct Directory {
+    entries: BTreeMap<String, Node>,
+}
+
+impl Node {
+    pub fn as_meta(&self, name: &str) -> Metadata {
+        match self {
+            Node::File(data) => Metadata::new(
+                name.to_owned(),
+                FileType::File,
+                data.read().len(),
+                None,
+                None,
+                None,
+            ),
+            Node::Directory(_) => {
+                Metadata::new(name.to_owned(), FileType::Directory, 0, None, None, None)
+            }
+            Node::Fifo => Metadata::new(name.to_owned(), FileType::Fifo, 0, None, None, None),
+        }
+    }
+}
+
+impl core::fmt::Debug for TmpFs {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TmpFs")
+            .field("entries", &self.root.read().entries.len())
+            .finish()
+    }
+}
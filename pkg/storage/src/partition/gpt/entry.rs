@@ -0,0 +1,115 @@
+//! GPT Partition Entry
+//!
+//! reference: <https://uefi.org/specs/UEFI/2.10/05_GUID_Partition_Table_Format.html#gpt-partition-entry-array>
+
+use super::*;
+
+/// Represents an entry of the partition entry array.
+#[derive(Clone, Copy)]
+pub struct GptPartition {
+    data: [u8; 128],
+}
+
+impl GptPartition {
+    /// The minimal size of an entry, larger entries are padded
+    pub const MIN_SIZE: usize = 128;
+
+    /// Parse a partition entry from the given data.
+    pub fn parse(data: &[u8]) -> GptPartition {
+        let mut entry = [0u8; 128];
+        entry.copy_from_slice(&data[..Self::MIN_SIZE]);
+        GptPartition { data: entry }
+    }
+
+    pub fn type_guid(&self) -> Guid {
+        Guid::new(self.type_guid_raw())
+    }
+
+    pub fn unique_guid(&self) -> Guid {
+        Guid::new(self.unique_guid_raw())
+    }
+
+    pub fn is_unused(&self) -> bool {
+        self.type_guid().is_unused()
+    }
+
+    /// Count of blocks in the partition, `last_lba` is inclusive
+    pub fn total_lba(&self) -> u64 {
+        self.last_lba()
+            .saturating_add(1)
+            .saturating_sub(self.first_lba())
+    }
+
+    /// The partition name, stored as null-terminated UTF-16LE
+    pub fn name(&self) -> String {
+        let units = self
+            .name_raw()
+            .chunks(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0);
+
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
+    define_field!([u8; 16], 0x00, type_guid_raw);
+    define_field!([u8; 16], 0x10, unique_guid_raw);
+    define_field!(u64, 0x20, first_lba);
+    define_field!(u64, 0x28, last_lba);
+    define_field!(u64, 0x30, attributes);
+    define_field!([u8; 72], 0x38, name_raw);
+}
+
+impl core::fmt::Debug for GptPartition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GPT Partition")
+            .field("Type GUID", &self.type_guid())
+            .field("Unique GUID", &self.unique_guid())
+            .field("First LBA", &format!("0x{:016x}", self.first_lba()))
+            .field("Last LBA", &format!("0x{:016x}", self.last_lba()))
+            .field("Attributes", &format!("0x{:016x}", self.attributes()))
+            .field("Name", &self.name())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpt_partition_test() {
+        let data = hex_literal::hex!(
+            "28 73 2a c1 1f f8 d2 11 ba 4b 00 a0 c9 3e c9 3b
+             11 11 11 11 22 22 33 33 44 44 55 55 55 55 55 55
+             00 08 00 00 00 00 00 00 ff 0f 00 00 00 00 00 00
+             00 00 00 00 00 00 00 00 45 00 46 00 49 00 20 00
+             73 00 79 00 73 00 74 00 65 00 6d 00 00 00 00 00"
+        );
+
+        let mut entry = [0u8; 128];
+        entry[..data.len()].copy_from_slice(&data);
+
+        let part = GptPartition::parse(&entry);
+
+        println!("{:#?}", part);
+
+        assert!(!part.is_unused());
+        assert_eq!(part.type_guid(), Guid::EFI_SYSTEM);
+        assert_eq!(
+            format!("{}", part.unique_guid()),
+            "11111111-2222-3333-4444-555555555555"
+        );
+        assert_eq!(part.first_lba(), 2048);
+        assert_eq!(part.last_lba(), 4095);
+        assert_eq!(part.total_lba(), 2048);
+        assert_eq!(part.attributes(), 0);
+        assert_eq!(part.name(), "EFI system");
+
+        // a corrupt entry ending at the largest LBA
+        entry[0x28..0x30].fill(0xff);
+        let part = GptPartition::parse(&entry);
+        assert_eq!(part.total_lba(), u64::MAX - 2048);
+    }
+}
@@ -0,0 +1,89 @@
+//! GUID
+//!
+//! reference: <https://en.wikipedia.org/wiki/Universally_unique_identifier#Encoding>
+
+/// A GUID as stored on the disk.
+///
+/// The first three groups are little-endian, the rest are big-endian,
+/// so `C12A7328-F81F-11D2-BA4B-00A0C93EC93B` is stored as
+/// `28 73 2A C1 1F F8 D2 11 BA 4B 00 A0 C9 3E C9 3B`.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Guid([u8; 16]);
+
+impl Guid {
+    /// Marks an unused partition entry
+    pub const UNUSED: Guid = Guid([0; 16]);
+    /// C12A7328-F81F-11D2-BA4B-00A0C93EC93B
+    pub const EFI_SYSTEM: Guid = Guid::from_fields(
+        0xC12A7328,
+        0xF81F,
+        0x11D2,
+        [0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B],
+    );
+    /// EBD0A0A2-B9E5-4433-87C0-68B6B72699C7
+    pub const BASIC_DATA: Guid = Guid::from_fields(
+        0xEBD0A0A2,
+        0xB9E5,
+        0x4433,
+        [0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7],
+    );
+    /// 0FC63DAF-8483-4772-8E79-3D69D8477DE4
+    pub const LINUX_FILESYSTEM: Guid = Guid::from_fields(
+        0x0FC63DAF,
+        0x8483,
+        0x4772,
+        [0x8E, 0x79, 0x3D, 0x69, 0xD8, 0x47, 0x7D, 0xE4],
+    );
+
+    /// Create a GUID from its on-disk bytes
+    pub fn new(data: &[u8; 16]) -> Self {
+        Guid(*data)
+    }
+
+    /// Create a GUID from the groups of its text form
+    pub const fn from_fields(d1: u32, d2: u16, d3: u16, d4: [u8; 8]) -> Self {
+        let d1 = d1.to_le_bytes();
+        let d2 = d2.to_le_bytes();
+        let d3 = d3.to_le_bytes();
+
+        Guid([
+            d1[0], d1[1], d1[2], d1[3], d2[0], d2[1], d3[0], d3[1], d4[0], d4[1], d4[2], d4[3],
+            d4[4], d4[5], d4[6], d4[7],
+        ])
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    pub fn is_unused(&self) -> bool {
+        *self == Self::UNUSED
+    }
+}
+
+impl core::fmt::Display for Guid {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let d = &self.0;
+        write!(
+            f,
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+            u32::from_le_bytes([d[0], d[1], d[2], d[3]]),
+            u16::from_le_bytes([d[4], d[5]]),
+            u16::from_le_bytes([d[6], d[7]]),
+            d[8],
+            d[9],
+            d[10],
+            d[11],
+            d[12],
+            d[13],
+            d[14],
+            d[15]
+        )
+    }
+}
+
+impl core::fmt::Debug for Guid {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -0,0 +1,170 @@
+//! GPT Header
+//!
+//! reference: <https://uefi.org/specs/UEFI/2.10/05_GUID_Partition_Table_Format.html#gpt-header>
+
+use super::*;
+
+/// Represents a GPT header.
+///
+/// The primary header is at LBA 1 and the backup header is at the last LBA,
+/// both describe the same partition entry array at different locations.
+pub struct GptHeader {
+    data: [u8; 512],
+}
+
+impl GptHeader {
+    pub const SIGNATURE: &'static [u8; 8] = b"EFI PART";
+    /// The minimal size of a header, the rest of the block is reserved
+    pub const MIN_SIZE: usize = 92;
+    /// The largest partition entry array accepted, far above the
+    /// 128 entries of 128 bytes that disks usually have
+    pub const MAX_ENTRY_ARRAY_SIZE: usize = 1 << 20;
+
+    /// Attempt to parse a header from the block at `lba`,
+    /// the signature, the size, the location and the CRC32 are validated.
+    pub fn new(data: &[u8], lba: u64) -> core::result::Result<GptHeader, &'static str> {
+        let data = data
+            .get(..512)
+            .and_then(|data| data.try_into().ok())
+            .ok_or("Bad GPT header size")?;
+        let header = GptHeader { data };
+
+        if header.signature() != Self::SIGNATURE {
+            return Err("Bad GPT signature");
+        }
+
+        let size = header.header_size() as usize;
+        if !(Self::MIN_SIZE..=512).contains(&size) {
+            return Err("Bad GPT header size");
+        }
+
+        // the CRC32 is computed with the CRC field zeroed
+        let mut crc = Crc32::new();
+        crc.update(&header.data[..0x10]);
+        crc.update(&[0; 4]);
+        crc.update(&header.data[0x14..size]);
+
+        if crc.finish() != header.header_crc32() {
+            return Err("Bad GPT header CRC32");
+        }
+
+        if header.my_lba() != lba {
+            return Err("Bad GPT header location");
+        }
+
+        if header.first_usable_lba() > header.last_usable_lba() {
+            return Err("Bad GPT usable range");
+        }
+
+        if header.partition_entry_size() < GptPartition::MIN_SIZE as u32
+            || !header.partition_entry_size().is_power_of_two()
+        {
+            return Err("Bad GPT partition entry size");
+        }
+
+        let array_size = header.partition_entry_count() as u64 * header.partition_entry_size() as u64;
+        if array_size > Self::MAX_ENTRY_ARRAY_SIZE as u64 {
+            return Err("Bad GPT partition entry count");
+        }
+
+        Ok(header)
+    }
+
+    /// Size of the partition entry array in bytes
+    pub fn entry_array_size(&self) -> usize {
+        self.partition_entry_count() as usize * self.partition_entry_size() as usize
+    }
+
+    pub fn disk_guid(&self) -> Guid {
+        Guid::new(self.disk_guid_raw())
+    }
+
+    define_field!([u8; 8], 0x00, signature);
+    define_field!(u32, 0x08, revision);
+    define_field!(u32, 0x0C, header_size);
+    define_field!(u32, 0x10, header_crc32);
+    define_field!(u64, 0x18, my_lba);
+    define_field!(u64, 0x20, alternate_lba);
+    define_field!(u64, 0x28, first_usable_lba);
+    define_field!(u64, 0x30, last_usable_lba);
+    define_field!([u8; 16], 0x38, disk_guid_raw);
+    define_field!(u64, 0x48, partition_entry_lba);
+    define_field!(u32, 0x50, partition_entry_count);
+    define_field!(u32, 0x54, partition_entry_size);
+    define_field!(u32, 0x58, partition_entry_array_crc32);
+}
+
+impl core::fmt::Debug for GptHeader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GPT Header")
+            .field("Revision", &format!("0x{:08x}", self.revision()))
+            .field("Header Size", &self.header_size())
+            .field("My LBA", &self.my_lba())
+            .field("Alternate LBA", &self.alternate_lba())
+            .field("First Usable LBA", &self.first_usable_lba())
+            .field("Last Usable LBA", &self.last_usable_lba())
+            .field("Disk GUID", &self.disk_guid())
+            .field("Partition Entry LBA", &self.partition_entry_lba())
+            .field("Partition Entry Count", &self.partition_entry_count())
+            .field("Partition Entry Size", &self.partition_entry_size())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpt_header() {
+        let data = hex_literal::hex!(
+            "45 46 49 20 50 41 52 54 00 00 01 00 5c 00 00 00
+             1a ef 14 f3 00 00 00 00 01 00 00 00 00 00 00 00
+             ff 1f 00 00 00 00 00 00 22 00 00 00 00 00 00 00
+             de 1f 00 00 00 00 00 00 ef be ad de 00 00 11 11
+             22 22 33 33 44 44 55 55 02 00 00 00 00 00 00 00
+             80 00 00 00 80 00 00 00 af de be 51"
+        );
+
+        let mut block = [0u8; 512];
+        block[..data.len()].copy_from_slice(&data);
+
+        let header = GptHeader::new(&block, 1).unwrap();
+
+        println!("{:#?}", header);
+
+        assert_eq!(header.revision(), 0x0001_0000);
+        assert_eq!(header.my_lba(), 1);
+        assert_eq!(header.alternate_lba(), 8191);
+        assert_eq!(header.first_usable_lba(), 34);
+        assert_eq!(header.last_usable_lba(), 8158);
+        assert_eq!(
+            format!("{}", header.disk_guid()),
+            "DEADBEEF-0000-1111-2222-333344445555"
+        );
+        assert_eq!(header.partition_entry_lba(), 2);
+        assert_eq!(header.partition_entry_count(), 128);
+        assert_eq!(header.partition_entry_size(), 128);
+        assert_eq!(header.entry_array_size(), 128 * 128);
+        assert_eq!(header.partition_entry_array_crc32(), 0x51bedeaf);
+
+        // a header found at another location is not valid
+        assert!(GptHeader::new(&block, 8191).is_err());
+
+        // an entry array too large to read, with a valid CRC32
+        let mut huge = block;
+        huge[0x50..0x54].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut crc = Crc32::new();
+        crc.update(&huge[..0x10]);
+        crc.update(&[0; 4]);
+        crc.update(&huge[0x14..0x5c]);
+        huge[0x10..0x14].copy_from_slice(&crc.finish().to_le_bytes());
+        assert_eq!(
+            GptHeader::new(&huge, 1).err(),
+            Some("Bad GPT partition entry count")
+        );
+
+        block[0x30] ^= 1;
+        assert!(GptHeader::new(&block, 1).is_err());
+    }
+}
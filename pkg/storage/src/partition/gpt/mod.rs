@@ -0,0 +1,217 @@
+//! GptTable
+
+mod crc32;
+mod entry;
+mod guid;
+mod header;
+
+use core::marker::PhantomData;
+
+use crate::mbr::MbrPartition;
+use crate::*;
+pub use crc32::*;
+pub use entry::*;
+pub use guid::*;
+pub use header::*;
+
+/// The GPT (GUID Partition Table)
+///
+/// The first block keeps a protective MBR so that legacy tools see the disk as in use,
+/// the primary header and entry array follow, and a backup copy of both
+/// is kept at the end of the disk.
+///
+/// [ Protective MBR | Header | Entries ] [ Partitions ] [ Entries | Backup Header ]
+pub struct GptTable<T, B>
+where
+    T: BlockDevice<B> + Clone,
+    B: BlockTrait,
+{
+    inner: T,
+    header: GptHeader,
+    partitions: Vec<(usize, GptPartition)>,
+    _block: PhantomData<B>,
+}
+
+/// The partition type of the protective MBR entry
+const PROTECTIVE_MBR_TYPE: u8 = 0xEE;
+
+impl<T, B> GptTable<T, B>
+where
+    T: BlockDevice<B> + Clone,
+    B: BlockTrait,
+{
+    /// Check whether the disk has a protective MBR
+    ///
+    /// The protective entry must be the only used entry of the MBR
+    /// and start right after it, at the GPT header.
+    pub fn is_gpt(inner: &T) -> bool {
+        let mut block = B::default();
+
+        if inner.read_block(0, &mut block).is_err() {
+            return false;
+        }
+
+        let buffer = block.as_ref();
+        if buffer[510..512] != [0x55, 0xAA] {
+            return false;
+        }
+
+        let mut used = (0..4).filter_map(|i| {
+            let offset = 446 + i * 16;
+            let entry: &[u8; 16] = buffer[offset..offset + 16].try_into().unwrap();
+            Some(MbrPartition::parse(entry)).filter(|entry| !entry.is_empty())
+        });
+
+        match (used.next(), used.next()) {
+            (Some(entry), None) => {
+                entry.partition_type() == PROTECTIVE_MBR_TYPE && entry.begin_lba() == 1
+            }
+            _ => false,
+        }
+    }
+
+    /// The header in use, the backup one if the primary is corrupted
+    pub fn header(&self) -> &GptHeader {
+        &self.header
+    }
+
+    /// All the used entries of the partition entry array,
+    /// with their slot index in the array
+    pub fn entries(&self) -> &[(usize, GptPartition)] {
+        &self.partitions
+    }
+
+    /// Read and validate the header at `lba` and the entry array it describes
+    fn read_table(inner: &T, lba: u64) -> Result<(GptHeader, Vec<(usize, GptPartition)>)> {
+        let mut block = B::default();
+        inner.read_block(lba as usize, &mut block)?;
+
+        let header = GptHeader::new(block.as_ref(), lba).map_err(|e| {
+            warn!("GPT header at LBA {}: {}", lba, e);
+            FsError::InvalidPartitionTable
+        })?;
+
+        let array_size = header.entry_array_size();
+        let mut array = Vec::with_capacity(array_size);
+        let mut lba = header.partition_entry_lba() as usize;
+
+        while array.len() < array_size {
+            inner.read_block(lba, &mut block)?;
+            let len = B::size().min(array_size - array.len());
+            array.extend_from_slice(&block.as_ref()[..len]);
+            lba += 1;
+        }
+
+        if crc32(&array) != header.partition_entry_array_crc32() {
+            warn!("GPT entry array at LBA {}: Bad CRC32", header.partition_entry_lba());
+            return Err(FsError::InvalidPartitionTable);
+        }
+
+        let partitions = array
+            .chunks(header.partition_entry_size() as usize)
+            .map(GptPartition::parse)
+            .enumerate()
+            .filter(|(_, part)| !part.is_unused())
+            .collect();
+
+        Ok((header, partitions))
+    }
+}
+
+impl<T, B> PartitionTable<T, B> for GptTable<T, B>
+where
+    T: BlockDevice<B> + Clone,
+    B: BlockTrait,
+{
+    fn parse(inner: T) -> Result<Self> {
+        if !Self::is_gpt(&inner) {
+            return Err(FsError::InvalidPartitionTable);
+        }
+
+        let (header, partitions) = match Self::read_table(&inner, 1) {
+            Ok(table) => table,
+            Err(e) => {
+                // the backup header is at the last block of the disk
+                let last_lba = inner.block_count()?.checked_sub(1).ok_or(e)? as u64;
+                warn!("Primary GPT is corrupted, trying the backup at LBA {}", last_lba);
+                Self::read_table(&inner, last_lba)?
+            }
+        };
+
+        for (i, part) in partitions.iter() {
+            trace!("Partition {}: {:#?}", i, part);
+        }
+
+        Ok(Self {
+            inner,
+            header,
+            partitions,
+            _block: PhantomData,
+        })
+    }
+
+    fn partitions(&self) -> Result<Vec<Partition<T, B>>> {
+        Ok(self
+            .partitions
+            .iter()
+            .map(|(_, part)| {
+                Partition::new(
+                    self.inner.clone(),
+                    part.first_lba() as usize,
+                    part.total_lba() as usize,
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Disk(Block512);
+
+    impl BlockDevice<Block512> for Disk {
+        fn block_count(&self) -> Result<usize> {
+            Ok(1)
+        }
+
+        fn read_block(&self, offset: usize, block: &mut Block512) -> Result<()> {
+            if offset != 0 {
+                return Err(FsError::InvalidOffset);
+            }
+            *block = self.0.clone();
+            Ok(())
+        }
+
+        fn write_block(&self, _offset: usize, _block: &Block512) -> Result<()> {
+            Err(FsError::ReadOnly)
+        }
+    }
+
+    fn mbr(entries: &[[u8; 16]]) -> Disk {
+        let mut block = Block512::default();
+        for (i, entry) in entries.iter().enumerate() {
+            block.as_mut()[446 + i * 16..462 + i * 16].copy_from_slice(entry);
+        }
+        block.as_mut()[510..512].copy_from_slice(&[0x55, 0xAA]);
+        Disk(block)
+    }
+
+    #[test]
+    fn test_protective_mbr() {
+        let protective = hex_literal::hex!("00 00 02 00 ee ff ff ff 01 00 00 00 ff ff ff ff");
+        let linux = hex_literal::hex!("00 00 00 00 83 00 00 00 00 08 00 00 00 08 00 00");
+        let shifted = hex_literal::hex!("00 00 02 00 ee ff ff ff 00 08 00 00 ff ff ff ff");
+
+        assert!(GptTable::is_gpt(&mbr(&[protective])));
+        assert!(GptTable::is_gpt(&mbr(&[[0; 16], protective])));
+
+        // a hybrid MBR also lists regular partitions
+        assert!(!GptTable::is_gpt(&mbr(&[protective, linux])));
+        // the protective entry must cover the GPT header
+        assert!(!GptTable::is_gpt(&mbr(&[shifted])));
+        assert!(!GptTable::is_gpt(&mbr(&[linux])));
+    }
+}
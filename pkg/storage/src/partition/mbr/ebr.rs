@@ -0,0 +1,193 @@
+//! Extended Boot Record
+//!
+//! reference: <https://en.wikipedia.org/wiki/Extended_boot_record>
+
+use super::*;
+use alloc::collections::BTreeSet;
+
+/// Upper bound of logical partitions in one extended partition
+const MAX_LOGICAL_PARTITIONS: usize = 128;
+
+/// A logical partition inside an extended partition.
+///
+/// Each logical partition is described by an EBR, the first entry of which
+/// locates the partition relative to the EBR itself, and the second entry
+/// locates the next EBR relative to the start of the extended partition.
+///
+/// [ EBR | Logical 1 ] [ EBR | Logical 2 ] ...
+#[derive(Clone, Copy)]
+pub struct LogicalPartition {
+    /// The LBA of the EBR that describes this partition
+    pub ebr_lba: u32,
+    /// The first entry of the EBR
+    pub entry: MbrPartition,
+}
+
+impl LogicalPartition {
+    /// The absolute LBA of the first sector of the partition,
+    /// `None` if it is past the end of a 32-bit LBA
+    pub fn begin_lba(&self) -> Option<u32> {
+        self.ebr_lba.checked_add(self.entry.begin_lba())
+    }
+
+    pub fn total_lba(&self) -> u32 {
+        self.entry.total_lba()
+    }
+}
+
+impl core::fmt::Debug for LogicalPartition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Logical Partition")
+            .field("EBR LBA", &format!("0x{:08x}", self.ebr_lba))
+            .field("Begin LBA", &self.begin_lba().map(|lba| format!("0x{:08x}", lba)))
+            .field("Total LBA", &format!("0x{:08x}", self.total_lba()))
+            .field("Partition Type", &format!("0x{:02x}", self.entry.partition_type()))
+            .finish()
+    }
+}
+
+/// Walk the EBR chain of an extended partition.
+///
+/// The walk stops at the end of the chain, at a sector that is not an EBR
+/// or cannot be read, or when a link points back to an EBR already visited.
+/// The partitions found up to there are kept.
+pub fn read_logical_partitions<T, B>(inner: &T, extended: &MbrPartition) -> Vec<LogicalPartition>
+where
+    T: BlockDevice<B>,
+    B: BlockTrait,
+{
+    let base = extended.begin_lba();
+    let mut ebr_lba = base;
+    let mut visited = BTreeSet::new();
+    let mut logical = Vec::new();
+    let mut block = B::default();
+
+    while logical.len() < MAX_LOGICAL_PARTITIONS {
+        if !visited.insert(ebr_lba) {
+            warn!("Loop in EBR chain at LBA {:#x}", ebr_lba);
+            break;
+        }
+
+        if let Err(e) = inner.read_block(ebr_lba as usize, &mut block) {
+            warn!("Failed to read EBR at LBA {:#x}: {:?}", ebr_lba, e);
+            break;
+        }
+        let buffer = block.as_ref();
+
+        if buffer[510..512] != [0x55, 0xAA] {
+            warn!("Bad EBR signature at LBA {:#x}", ebr_lba);
+            break;
+        }
+
+        let entry = |i: usize| {
+            let offset = 446 + i * 16;
+            MbrPartition::parse(buffer[offset..offset + 16].try_into().unwrap())
+        };
+        let (current, next) = (entry(0), entry(1));
+
+        let part = LogicalPartition {
+            ebr_lba,
+            entry: current,
+        };
+        if part.begin_lba().is_none() {
+            warn!("Logical partition out of range in EBR at LBA {:#x}", ebr_lba);
+        } else if !current.is_empty() {
+            logical.push(part);
+        }
+
+        if next.is_empty() || !next.is_extended() {
+            break;
+        }
+
+        ebr_lba = match base.checked_add(next.begin_lba()) {
+            Some(lba) => lba,
+            None => break,
+        };
+    }
+
+    logical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Disk(Vec<Block512>);
+
+    impl BlockDevice<Block512> for Disk {
+        fn block_count(&self) -> Result<usize> {
+            Ok(self.0.len())
+        }
+
+        fn read_block(&self, offset: usize, block: &mut Block512) -> Result<()> {
+            *block = self.0.get(offset).ok_or(FsError::InvalidOffset)?.clone();
+            Ok(())
+        }
+
+        fn write_block(&self, _offset: usize, _block: &Block512) -> Result<()> {
+            Err(FsError::ReadOnly)
+        }
+    }
+
+    fn ebr(entries: &[[u8; 16]]) -> Block512 {
+        let mut block = Block512::default();
+        for (i, entry) in entries.iter().enumerate() {
+            block.as_mut()[446 + i * 16..462 + i * 16].copy_from_slice(entry);
+        }
+        block.as_mut()[510..512].copy_from_slice(&[0x55, 0xAA]);
+        block
+    }
+
+    #[test]
+    fn test_ebr_chain() {
+        // extended partition at LBA 100, logical partitions at 110 and 220
+        let extended = MbrPartition::parse(&hex_literal::hex!(
+            "00 00 00 00 05 00 00 00 64 00 00 00 c8 00 00 00"
+        ));
+
+        let mut disk = Disk(vec![Block512::default(); 300]);
+        disk.0[100] = ebr(&[
+            hex_literal::hex!("00 00 00 00 83 00 00 00 0a 00 00 00 32 00 00 00"),
+            hex_literal::hex!("00 00 00 00 05 00 00 00 6e 00 00 00 3c 00 00 00"),
+        ]);
+        disk.0[210] = ebr(&[
+            hex_literal::hex!("00 00 00 00 0b 00 00 00 0a 00 00 00 14 00 00 00"),
+        ]);
+
+        let logical = read_logical_partitions(&disk, &extended);
+
+        assert_eq!(logical.len(), 2);
+        assert_eq!(logical[0].begin_lba(), Some(110));
+        assert_eq!(logical[0].total_lba(), 50);
+        assert_eq!(logical[0].entry.partition_type(), 0x83);
+        assert_eq!(logical[1].ebr_lba, 210);
+        assert_eq!(logical[1].begin_lba(), Some(220));
+        assert_eq!(logical[1].total_lba(), 20);
+
+        // link the second EBR back to the first one
+        disk.0[210] = ebr(&[
+            hex_literal::hex!("00 00 00 00 0b 00 00 00 0a 00 00 00 14 00 00 00"),
+            hex_literal::hex!("00 00 00 00 05 00 00 00 00 00 00 00 3c 00 00 00"),
+        ]);
+
+        let logical = read_logical_partitions(&disk, &extended);
+        assert_eq!(logical.len(), 2);
+
+        // link past the end of the disk, the first partition is kept
+        disk.0[100] = ebr(&[
+            hex_literal::hex!("00 00 00 00 83 00 00 00 0a 00 00 00 32 00 00 00"),
+            hex_literal::hex!("00 00 00 00 05 00 00 00 00 10 00 00 3c 00 00 00"),
+        ]);
+
+        let logical = read_logical_partitions(&disk, &extended);
+        assert_eq!(logical.len(), 1);
+        assert_eq!(logical[0].begin_lba(), Some(110));
+
+        // a logical partition past the end of a 32-bit LBA is skipped
+        disk.0[100] = ebr(&[hex_literal::hex!(
+            "00 00 00 00 83 00 00 00 ff ff ff ff 32 00 00 00"
+        )]);
+
+        assert!(read_logical_partitions(&disk, &extended).is_empty());
+    }
+}
@@ -39,6 +39,16 @@ impl MbrPartition {
         self.status() == 0x80
     }
 
+    /// An unused slot of the partition table
+    pub fn is_empty(&self) -> bool {
+        self.partition_type() == 0x00 || self.total_lba() == 0
+    }
+
+    /// An extended partition holds logical partitions instead of a filesystem
+    pub fn is_extended(&self) -> bool {
+        matches!(self.partition_type(), 0x05 | 0x0F | 0x85)
+    }
+
     pub fn begin_head(&self) -> u8 {
         self.data[0x01]
     }
@@ -1,10 +1,12 @@
 //! MbrTable
 
+mod ebr;
 mod entry;
 
 use core::marker::PhantomData;
 
 use crate::*;
+pub use ebr::*;
 pub use entry::*;
 
 /// The MBR Table
@@ -14,6 +16,9 @@ pub use entry::*;
 /// The MBR contains information about the partitions.
 ///
 /// [ MBR | Partitions ] [ Partition 1 ] [ Partition 2 ] [ Partition 3 ] [ Partition 4 ]
+///
+/// An extended partition takes one of the four slots and holds
+/// a chain of logical partitions, see `LogicalPartition`.
 pub struct MbrTable<T, B>
 where
     T: BlockDevice<B> + Clone,
@@ -21,9 +26,26 @@ where
 {
     inner: T,
     partitions: [MbrPartition; 4],
+    logical: Vec<LogicalPartition>,
     _block: PhantomData<B>,
 }
 
+impl<T, B> MbrTable<T, B>
+where
+    T: BlockDevice<B> + Clone,
+    B: BlockTrait,
+{
+    /// The four primary entries, including the unused ones
+    pub fn entries(&self) -> &[MbrPartition; 4] {
+        &self.partitions
+    }
+
+    /// The logical partitions of the extended partitions, in chain order
+    pub fn logical_entries(&self) -> &[LogicalPartition] {
+        &self.logical
+    }
+}
+
 impl<T, B> PartitionTable<T, B> for MbrTable<T, B>
 where
     T: BlockDevice<B> + Clone,
@@ -46,14 +68,25 @@ where
             let partition = MbrPartition::parse(&partition_data);
             partitions.push(partition);
 
-            if partitions[i].is_active() {
+            if !partitions[i].is_empty() {
                 trace!("Partition {}: {:#?}", i, partitions[i]);
             }
         }
 
+        let mut logical = Vec::new();
+
+        for part in partitions.iter().filter(|part| part.is_extended()) {
+            logical.extend(read_logical_partitions(&inner, part));
+        }
+
+        for (i, part) in logical.iter().enumerate() {
+            trace!("Logical Partition {}: {:#?}", i, part);
+        }
+
         Ok(Self {
             inner,
             partitions: partitions.try_into().unwrap(),
+            logical,
             _block: PhantomData,
         })
     }
@@ -62,7 +95,7 @@ where
         let mut parts = Vec::new();
 
         for part in self.partitions {
-            if part.is_active() {
+            if !part.is_empty() && !part.is_extended() {
                 parts.push(Partition::new(
                     self.inner.clone(),
                     part.begin_lba() as usize,
@@ -71,6 +104,16 @@ where
             }
         }
 
+        for part in self.logical.iter() {
+            if let Some(begin) = part.begin_lba().filter(|_| !part.entry.is_empty()) {
+                parts.push(Partition::new(
+                    self.inner.clone(),
+                    begin as usize,
+                    part.total_lba() as usize,
+                ));
+            }
+        }
+
         Ok(parts)
     }
 }
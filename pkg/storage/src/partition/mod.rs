@@ -2,6 +2,7 @@ use core::marker::PhantomData;
 
 use crate::*;
 
+pub mod gpt;
 pub mod mbr;
 
 /// Partition table trait
@@ -0,0 +1,145 @@
+//! Records shared by the kernel and user space for filesystem syscalls
+
+use num_enum::TryFromPrimitive;
+
+/// The type of a directory entry
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum FileKind {
+    File = 0,
+    Directory = 1,
+    Symlink = 2,
+    Fifo = 3,
+}
+
+/// The fixed part of a record filled by `Syscall::GetDents`
+///
+/// Every record is this header followed by `name_len` bytes of UTF-8 name,
+/// padded to a multiple of 8 bytes. `reclen` is the length of the whole record.
+/// Timestamps are seconds since the Unix epoch, `Dirent::NO_TIME` if unknown.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Dirent {
+    pub reclen: u16,
+    pub name_len: u16,
+    pub kind: FileKind,
+    _reserved: [u8; 3],
+    pub size: u64,
+    pub created: i64,
+    pub modified: i64,
+    pub accessed: i64,
+}
+
+impl Dirent {
+    pub const NO_TIME: i64 = i64::MIN;
+    pub const HEADER_LEN: usize = core::mem::size_of::<Self>();
+
+    pub fn new(kind: FileKind, size: u64, created: i64, modified: i64, accessed: i64) -> Self {
+        Self {
+            reclen: 0,
+            name_len: 0,
+            kind,
+            _reserved: [0; 3],
+            size,
+            created,
+            modified,
+            accessed,
+        }
+    }
+
+    /// The length of a record with a name of `name_len` bytes
+    pub const fn record_len(name_len: usize) -> usize {
+        (Self::HEADER_LEN + name_len + 7) & !7
+    }
+
+    /// Write the record with `name` to the start of `buf`
+    ///
+    /// Returns the length of the record, or `None` if `buf` is too small.
+    pub fn encode(mut self, name: &str, buf: &mut [u8]) -> Option<usize> {
+        let len = Self::record_len(name.len());
+
+        if buf.len() < len || name.len() > u16::MAX as usize || len > u16::MAX as usize {
+            return None;
+        }
+
+        self.reclen = len as u16;
+        self.name_len = name.len() as u16;
+
+        // SAFETY: the buffer is long enough and the header is plain data
+        unsafe { core::ptr::write_unaligned(buf.as_mut_ptr() as *mut Self, self) };
+
+        let name_end = Self::HEADER_LEN + name.len();
+        buf[Self::HEADER_LEN..name_end].copy_from_slice(name.as_bytes());
+        buf[name_end..len].fill(0);
+
+        Some(len)
+    }
+
+    /// Read the record at the start of `buf`, returning it with its name
+    pub fn decode(buf: &[u8]) -> Option<(Self, &str)> {
+        if buf.len() < Self::HEADER_LEN {
+            return None;
+        }
+
+        // the kind is checked before reading the header as `Self`
+        FileKind::try_from(buf[4]).ok()?;
+
+        // SAFETY: the buffer is long enough and the kind is valid
+        let dirent = unsafe { core::ptr::read_unaligned(buf.as_ptr() as *const Self) };
+
+        let name_end = Self::HEADER_LEN + dirent.name_len as usize;
+        if (dirent.reclen as usize) < name_end || buf.len() < dirent.reclen as usize {
+            return None;
+        }
+
+        let name = core::str::from_utf8(&buf[Self::HEADER_LEN..name_end]).ok()?;
+        Some((dirent, name))
+    }
+
+    /// Turn a timestamp into `None` if it is unknown
+    pub fn time(time: i64) -> Option<i64> {
+        Some(time).filter(|&time| time != Self::NO_TIME)
+    }
+}
+
+/// The metadata filled by `Syscall::FileStat` and `Syscall::FStat`
+///
+/// Timestamps are the same as in `Dirent`. The POSIX fields are
+/// only known on filesystems that store them, otherwise `mode` is 0.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Stat {
+    pub kind: FileKind,
+    /// File type and permission bits, as in `st_mode`
+    pub mode: u16,
+    pub links: u16,
+    pub uid: u32,
+    pub gid: u32,
+    pub size: u64,
+    pub created: i64,
+    pub modified: i64,
+    pub accessed: i64,
+}
+
+impl Stat {
+    pub fn is_file(&self) -> bool {
+        self.kind == FileKind::File
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == FileKind::Directory
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.kind == FileKind::Symlink
+    }
+
+    pub fn is_fifo(&self) -> bool {
+        self.kind == FileKind::Fifo
+    }
+
+    /// The permission bits of the mode, if known
+    pub fn permissions(&self) -> Option<u16> {
+        (self.mode != 0).then_some(self.mode & 0o7777)
+    }
+}
@@ -1,14 +1,33 @@
 #![no_std]
 
-use num_enum::FromPrimitive;
+use num_enum::{FromPrimitive, TryFromPrimitive};
 
+pub mod fs;
 pub mod macros;
+pub mod signal;
+
+pub use fs::*;
+pub use signal::*;
 
 #[repr(usize)]
 #[derive(Clone, Debug, FromPrimitive)]
 pub enum Syscall {
     Read = 0,
     Write = 1,
+    Open = 2,
+    Close = 3,
+    FileStat = 4,
+    FStat = 5,
+
+    Seek = 8,
+    SigAction = 13,
+    SigProcMask = 14,
+    SigReturn = 15,
+    PRead = 17,
+    Pipe = 22,
+
+    Dup = 32,
+    Dup2 = 33,
 
     GetPid = 39,
     
@@ -16,7 +35,29 @@ pub enum Syscall {
     Spawn = 59,
     Exit = 60,
     WaitPid = 61,
+    Kill = 62,
+
+    MsgGet = 68,
+    MsgSnd = 69,
+    MsgRcv = 70,
+    MsgCtl = 71,
+
+    Rename = 82,
+    Unlink = 87,
+
+    SetPgid = 109,
 
+    MkFifo = 133,
+
+    Mount = 165,
+    Umount = 166,
+
+    Futex = 202,
+
+    GetDents = 217,
+
+    SetForeground = 65525,
+    Shm = 65526,
     Cat = 65527,
     ListDir = 65528,
     Sem = 65529,
@@ -29,3 +70,134 @@ pub enum Syscall {
     #[num_enum(default)]
     Unknown = 65535,
 }
+
+/// The flags of `Syscall::Open`, combined with `|`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenFlags(pub usize);
+
+impl OpenFlags {
+    pub const READ: Self = Self(1 << 0);
+    pub const WRITE: Self = Self(1 << 1);
+    /// Create the file if it does not exist
+    pub const CREATE: Self = Self(1 << 2);
+    /// Truncate the file to zero length, requires `WRITE`
+    pub const TRUNCATE: Self = Self(1 << 3);
+    /// Every write goes to the end of the file, requires `WRITE`
+    pub const APPEND: Self = Self(1 << 4);
+    /// Open a directory to list it with `Syscall::GetDents`
+    pub const DIRECTORY: Self = Self(1 << 5);
+    /// Leave the descriptors of `Syscall::Pipe` out of spawned processes
+    pub const CLOEXEC: Self = Self(1 << 6);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for OpenFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The flags of the message queue syscalls, combined with `|`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MsgFlags(pub usize);
+
+impl MsgFlags {
+    pub const NONE: Self = Self(0);
+    /// `Syscall::MsgGet` creates the queue if it does not exist
+    pub const CREATE: Self = Self(1 << 0);
+    /// Fail instead of waiting for room or for a message
+    pub const NOWAIT: Self = Self(1 << 1);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for MsgFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The flags of `Syscall::WaitPid`, combined with `|`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaitFlags(pub usize);
+
+impl WaitFlags {
+    pub const NONE: Self = Self(0);
+    /// Return 0 at once if no child has exited yet
+    pub const NOHANG: Self = Self(1 << 0);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for WaitFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The flags of a new semaphore, combined with `|`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SemFlags(pub usize);
+
+impl SemFlags {
+    pub const NONE: Self = Self(0);
+    /// Any process may open the semaphore by key, not only its
+    /// creator and the processes forked after it was created
+    pub const SHARED: Self = Self(1 << 0);
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The errors of the semaphore operations, which return 0 on success
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum SemError {
+    /// No such semaphore, or it is removed while waiting
+    NotExist = 1,
+    /// `trywait` finds the count at 0
+    WouldBlock = 2,
+    /// `timedwait` is not signaled in time
+    TimedOut = 3,
+}
+
+/// The operations of `Syscall::Futex`
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum FutexOp {
+    /// Block while the word at the address holds the value
+    Wait = 0,
+    /// Wake up as many waiters as the value at most
+    Wake = 1,
+}
+
+/// The commands of `Syscall::MsgCtl`
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum MsgCmd {
+    /// Remove the queue, failing the processes waiting on it
+    Remove = 0,
+}
+
+/// The reference point of `Syscall::Seek`, same as `SEEK_SET`, `SEEK_CUR` and `SEEK_END`
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum Whence {
+    Start = 0,
+    Current = 1,
+    End = 2,
+}
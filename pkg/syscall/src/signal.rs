@@ -0,0 +1,49 @@
+use num_enum::TryFromPrimitive;
+
+/// The signals a process can send or receive, numbered as on Linux
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum Signal {
+    Hup = 1,
+    Int = 2,
+    Quit = 3,
+    Kill = 9,
+    Usr1 = 10,
+    Usr2 = 12,
+    Pipe = 13,
+    Alrm = 14,
+    Term = 15,
+    Chld = 17,
+}
+
+impl Signal {
+    /// The bit of the signal in a mask of `Syscall::SigProcMask`
+    pub const fn mask(self) -> u64 {
+        1 << self as usize
+    }
+
+    /// `Kill` can be neither caught, ignored nor blocked
+    pub const fn catchable(self) -> bool {
+        !matches!(self, Signal::Kill)
+    }
+
+    /// Whether the default action is to ignore the signal,
+    /// the process is terminated otherwise
+    pub const fn ignored_by_default(self) -> bool {
+        matches!(self, Signal::Chld)
+    }
+}
+
+/// The handler of `Syscall::SigAction` to take the default action
+pub const SIG_DFL: usize = 0;
+/// The handler of `Syscall::SigAction` to ignore the signal
+pub const SIG_IGN: usize = 1;
+
+/// How `Syscall::SigProcMask` changes the blocked signals
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+pub enum SigHow {
+    Block = 0,
+    Unblock = 1,
+    SetMask = 2,
+}
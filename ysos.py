@@ -23,6 +23,8 @@ parser.add_argument('--dry-run', action='store_true', help='Enable dry run')
 parser.add_argument('--bios', type=str,
                     default=os.path.join('assets', 'OVMF.fd'), help='Set BIOS path')
 parser.add_argument('--boot', type=str, default='esp', help='Set boot path')
+parser.add_argument('--disk', type=str, action='append', default=[],
+                    help='Attach an extra raw disk image, can be repeated')
 
 parser.add_argument('task', type=str, choices=[
                     'build', 'clean', 'launch', 'run'
@@ -85,6 +87,10 @@ def qemu(output: str = '-nographic', memory: str = '96M', debug: bool = False, i
     qemu_args = [qemu_exe, '-bios', args.bios, '-net', 'none', *output.split(),
                  '-m', memory, '-drive', 'format=raw,file=fat:rw:esp']
 
+    # extra disks are mounted at /mnt/hdXN by the kernel
+    for disk in args.disk:
+        qemu_args += ['-drive', f'format=raw,file={disk}']
+
     if debug:
         qemu_args += ['-s', '-S']
     elif intdbg: